@@ -0,0 +1,97 @@
+//! 四叉树与线性扫描的对比，运行 `cargo bench --bench spatial`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use rust_helloworld::geometry::{Point, QuadTree, Rectangle};
+
+const ITEMS: usize = 20_000;
+const QUERIES: usize = 2_000;
+const WORLD: f64 = 10_000.0;
+
+fn random_rect(rng: &mut impl Rng, max_size: f64) -> Rectangle<f64> {
+    Rectangle::at(
+        Point::new(rng.gen_range(0.0..WORLD), rng.gen_range(0.0..WORLD)),
+        rng.gen_range(0.0..max_size),
+        rng.gen_range(0.0..max_size),
+    )
+}
+
+fn distance(rect: &Rectangle<f64>, p: &Point<f64>) -> f64 {
+    let dx = (rect.min_x() - p.x).max(p.x - rect.max_x()).max(0.0);
+    let dy = (rect.min_y() - p.y).max(p.y - rect.max_y()).max(0.0);
+    dx.hypot(dy)
+}
+
+fn time<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    println!("{:<28} {:>10.2?}", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let rects: Vec<Rectangle<f64>> = (0..ITEMS).map(|_| random_rect(&mut rng, 50.0)).collect();
+    let queries: Vec<Rectangle<f64>> = (0..QUERIES).map(|_| random_rect(&mut rng, 200.0)).collect();
+    let points: Vec<Point<f64>> = queries.iter().map(|q| q.origin).collect();
+
+    let mut tree = QuadTree::new(Rectangle::new(WORLD + 50.0, WORLD + 50.0));
+    time("quadtree insert", || {
+        for (i, rect) in rects.iter().enumerate() {
+            tree.insert(*rect, i);
+        }
+    });
+
+    let touches = |a: &Rectangle<f64>, b: &Rectangle<f64>| {
+        a.min_x() <= b.max_x()
+            && b.min_x() <= a.max_x()
+            && a.min_y() <= b.max_y()
+            && b.min_y() <= a.max_y()
+    };
+
+    time("quadtree range query", || {
+        for q in &queries {
+            black_box(tree.query(q));
+        }
+    });
+    time("linear range query", || {
+        for q in &queries {
+            black_box(rects.iter().filter(|r| touches(r, q)).count());
+        }
+    });
+
+    time("quadtree nearest", || {
+        for p in &points {
+            black_box(tree.nearest(p));
+        }
+    });
+    time("linear nearest", || {
+        for p in &points {
+            let nearest = rects
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| distance(a, p).total_cmp(&distance(b, p)));
+            black_box(nearest);
+        }
+    });
+
+    time("quadtree overlapping pairs", || {
+        black_box(tree.overlapping_pairs());
+    });
+    // 线性扫描是 O(n²)，只取一部分数据，避免跑得太久
+    let sample = &rects[..ITEMS / 4];
+    time("linear overlapping pairs /4", || {
+        let mut count = 0;
+        for (i, a) in sample.iter().enumerate() {
+            for b in &sample[i + 1..] {
+                if a.intersects(b) {
+                    count += 1;
+                }
+            }
+        }
+        black_box(count);
+    });
+}
@@ -0,0 +1,250 @@
+//! 动作解释器：把 [`Action`] 应用到光标上，并记录历史以便撤销和重做
+use std::fmt::{Display, Formatter};
+
+use crate::geometry::Point;
+use crate::i18n::tr;
+use crate::kinds::Rgba;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Say(String),
+    MoveTo(i32, i32),
+    /// 通道用 `u16` 表示，执行前会检查是否在 0 到 255 之间
+    ChangeColorRGB(u16, u16, u16),
+    /// 沿当前朝向前进，负数为后退
+    Forward(i32),
+    /// 右转的角度，负数为左转
+    Turn(i32),
+    PenUp,
+    PenDown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    /// 颜色通道超出 0 到 255，`channel` 为 `'r'`、`'g'` 或 `'b'`
+    ChannelOutOfRange { channel: char, value: u16 },
+    /// 前进之后的坐标超出 `i32` 的范围
+    PositionOutOfRange { x: i64, y: i64 },
+}
+
+impl Display for ActionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ActionError::ChannelOutOfRange { channel, value } => tr(
+                "error.action.channel",
+                &[("channel", channel), ("value", value)],
+            ),
+            ActionError::PositionOutOfRange { x, y } => {
+                tr("error.message.out_of_range", &[("x", x), ("y", y)])
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+fn channel(channel: char, value: u16) -> Result<u8, ActionError> {
+    u8::try_from(value).map_err(|_| ActionError::ChannelOutOfRange { channel, value })
+}
+
+impl Action {
+    /// 检查动作的参数，不合法的动作不会被执行
+    pub fn validate(&self) -> Result<(), ActionError> {
+        if let Action::ChangeColorRGB(r, g, b) = *self {
+            channel('r', r)?;
+            channel('g', g)?;
+            channel('b', b)?;
+        }
+        Ok(())
+    }
+}
+
+/// 可以执行动作的对象，脚本可以在任何实现了它的对象上运行
+pub trait ActionTarget {
+    fn apply(&mut self, action: Action) -> Result<(), ActionError>;
+}
+
+/// 动作作用的对象
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub position: Point<i32>,
+    pub color: Rgba,
+    /// 最近一次说的话
+    pub speech: Option<String>,
+    /// 朝向，单位为度，取 0 到 359，0 度朝右，y 轴向下
+    pub heading: i32,
+    pub pen_down: bool,
+}
+
+impl Default for Cursor {
+    fn default() -> Cursor {
+        Cursor {
+            position: Point::origin(),
+            color: Rgba::BLACK,
+            speech: None,
+            heading: 0,
+            pen_down: true,
+        }
+    }
+}
+
+impl Cursor {
+    /// 沿朝向前进 `distance` 之后的位置，按四舍五入取整
+    /// ```rust
+    /// use rust_helloworld::action::Cursor;
+    /// use rust_helloworld::geometry::Point;
+    ///
+    /// let mut cursor = Cursor::default();
+    /// cursor.heading = 90;
+    /// assert_eq!(cursor.ahead(10), Ok(Point::new(0, 10)));
+    /// cursor.heading = 45;
+    /// assert_eq!(cursor.ahead(10), Ok(Point::new(7, 7)));
+    /// ```
+    pub fn ahead(&self, distance: i32) -> Result<Point<i32>, ActionError> {
+        let (sin, cos) = (self.heading as f64).to_radians().sin_cos();
+        let x = (self.position.x as f64 + cos * distance as f64).round();
+        let y = (self.position.y as f64 + sin * distance as f64).round();
+        let range = i32::MIN as f64..=i32::MAX as f64;
+        if range.contains(&x) && range.contains(&y) {
+            Ok(Point::new(x as i32, y as i32))
+        } else {
+            Err(ActionError::PositionOutOfRange {
+                x: x as i64,
+                y: y as i64,
+            })
+        }
+    }
+}
+
+/// 执行动作并记录历史
+/// ```rust
+/// use rust_helloworld::action::{Action, ActionError, Interpreter};
+/// use rust_helloworld::geometry::Point;
+/// use rust_helloworld::kinds::Rgba;
+///
+/// let mut interpreter = Interpreter::new();
+/// interpreter
+///     .run(vec![
+///         Action::Say("Hello Rust".to_string()),
+///         Action::MoveTo(1, 2),
+///         Action::ChangeColorRGB(255, 255, 0),
+///     ])
+///     .unwrap();
+/// assert_eq!(interpreter.cursor().color, Rgba::rgb(255, 255, 0));
+///
+/// // 超出范围的通道会被拒绝，而不是被忽略
+/// assert_eq!(
+///     interpreter.apply(Action::ChangeColorRGB(256, 0, 0)),
+///     Err(ActionError::ChannelOutOfRange { channel: 'r', value: 256 })
+/// );
+/// assert_eq!(interpreter.history().len(), 3);
+///
+/// assert_eq!(interpreter.undo(), Some(Action::ChangeColorRGB(255, 255, 0)));
+/// assert_eq!(interpreter.undo(), Some(Action::MoveTo(1, 2)));
+/// assert_eq!(interpreter.cursor().position, Point::new(0, 0));
+/// assert_eq!(interpreter.cursor().color, Rgba::BLACK);
+///
+/// assert_eq!(interpreter.redo(), Some(Action::MoveTo(1, 2)));
+/// assert_eq!(interpreter.cursor().position, Point::new(1, 2));
+///
+/// // 执行新的动作后不能再重做
+/// interpreter.apply(Action::Say("bye".to_string())).unwrap();
+/// assert_eq!(interpreter.redo(), None);
+/// assert_eq!(interpreter.cursor().speech.as_deref(), Some("bye"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interpreter {
+    cursor: Cursor,
+    /// 已执行的动作和执行前的光标
+    done: Vec<(Action, Cursor)>,
+    undone: Vec<Action>,
+}
+
+impl ActionTarget for Interpreter {
+    fn apply(&mut self, action: Action) -> Result<(), ActionError> {
+        Interpreter::apply(self, action)
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        Interpreter::default()
+    }
+
+    pub fn with_cursor(cursor: Cursor) -> Interpreter {
+        Interpreter {
+            cursor,
+            ..Interpreter::default()
+        }
+    }
+
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// 已执行（且没有被撤销）的动作，按执行顺序排列
+    pub fn history(&self) -> Vec<&Action> {
+        self.done.iter().map(|(action, _)| action).collect()
+    }
+
+    /// 执行一个动作，会清空重做记录
+    pub fn apply(&mut self, action: Action) -> Result<(), ActionError> {
+        self.perform(action)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// 依次执行，遇到不合法的动作时停下，之前的动作仍然有效
+    pub fn run<I: IntoIterator<Item = Action>>(&mut self, actions: I) -> Result<(), ActionError> {
+        for action in actions {
+            self.apply(action)?;
+        }
+        Ok(())
+    }
+
+    fn perform(&mut self, action: Action) -> Result<(), ActionError> {
+        action.validate()?;
+        let before = self.cursor.clone();
+        match &action {
+            Action::Say(words) => self.cursor.speech = Some(words.clone()),
+            Action::MoveTo(x, y) => self.cursor.position = Point::new(*x, *y),
+            // 已经检查过范围
+            Action::ChangeColorRGB(r, g, b) => {
+                self.cursor.color = Rgba::rgb(*r as u8, *g as u8, *b as u8)
+            }
+            Action::Forward(distance) => self.cursor.position = self.cursor.ahead(*distance)?,
+            Action::Turn(degrees) => {
+                self.cursor.heading = (self.cursor.heading + degrees % 360).rem_euclid(360)
+            }
+            Action::PenUp => self.cursor.pen_down = false,
+            Action::PenDown => self.cursor.pen_down = true,
+        }
+        self.done.push((action, before));
+        Ok(())
+    }
+
+    /// 撤销最近的一个动作，返回被撤销的动作
+    pub fn undo(&mut self) -> Option<Action> {
+        let (action, before) = self.done.pop()?;
+        self.cursor = before;
+        self.undone.push(action.clone());
+        Some(action)
+    }
+
+    /// 重做最近撤销的一个动作，返回被重做的动作
+    pub fn redo(&mut self) -> Option<Action> {
+        let action = self.undone.pop()?;
+        self.perform(action.clone())
+            .expect("undone actions were valid when first applied");
+        Some(action)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+}
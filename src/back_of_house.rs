@@ -1,6 +1,28 @@
-pub fn fix_incorrect_order() {
-    cook_order();
-    crate::front_of_house::serving::serve_order();
+use crate::events::{self, OrderEvent, OrderEventKind};
+use crate::front_of_house::serving::Order;
+use crate::storage::{Storage, StorageError};
+use crate::utils::now;
+
+/// 重新做一份，然后再次上菜
+pub fn fix_incorrect_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+) -> Result<Option<Order>, StorageError> {
+    let event = OrderEvent::new(order_id, now(), actor, OrderEventKind::Remade);
+    if events::commit(store, event)?.is_none() {
+        return Ok(None);
+    }
+    cook_order(store, actor, order_id)?;
+    crate::front_of_house::serving::serve_order(store, actor, order_id)
 }
 
-pub fn cook_order() {}
+/// 做菜，订单不存在时返回 None
+pub fn cook_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+) -> Result<Option<Order>, StorageError> {
+    let event = OrderEvent::new(order_id, now(), actor, OrderEventKind::Cooked);
+    events::commit(store, event)
+}
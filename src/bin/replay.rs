@@ -0,0 +1,50 @@
+//! 回放数据文件中的订单事件，用来核对有争议的订单
+//!
+//! 用法: `replay <数据文件> [订单号]`，不指定订单号时输出所有订单的当前状态
+use std::env;
+use std::process;
+
+use rust_helloworld::events::{history, project};
+use rust_helloworld::storage::{JsonLinesStorage, Storage};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: {} <data.jsonl> [order_id]", args[0]);
+        process::exit(2);
+    }
+
+    let events = match JsonLinesStorage::load(&args[1]).and_then(|store| store.events()) {
+        Ok(events) => events,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    match args.get(2).map(|id| id.parse::<u64>()) {
+        None => {
+            for order in project(&events).values() {
+                println!(
+                    "#{} table {} {:?} total {}",
+                    order.id,
+                    order.table,
+                    order.status,
+                    order.total()
+                );
+            }
+        }
+        Some(Ok(order_id)) => {
+            for (event, order) in history(&events, order_id) {
+                println!(
+                    "{} {} {:?} -> {:?}",
+                    event.at, event.actor, event.kind, order.status
+                );
+            }
+        }
+        Some(Err(err)) => {
+            eprintln!("invalid order id: {}", err);
+            process::exit(2);
+        }
+    }
+}
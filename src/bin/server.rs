@@ -0,0 +1,34 @@
+//! 启动前台 HTTP 服务
+//!
+//! 用法: `server [监听地址] [数据文件]`，默认监听 `127.0.0.1:8080`，数据写入 `restaurant.jsonl`
+use std::env;
+use std::process;
+
+use rust_helloworld::server::Server;
+use rust_helloworld::storage::JsonLinesStorage;
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let path = env::args()
+        .nth(2)
+        .unwrap_or_else(|| "restaurant.jsonl".to_string());
+
+    let store = match JsonLinesStorage::open(&path) {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    let server = match Server::bind(&addr, Box::new(store)) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    println!("listening on {}", addr);
+    server.run();
+}
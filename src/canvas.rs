@@ -0,0 +1,211 @@
+//! 软件光栅化画布：把图形填充、描边到 RGBA 像素缓冲区，并保存为 PPM 或 PNG
+//!
+//! 像素 `(x, y)` 覆盖平面上 `[x, x+1) × [y, y+1)` 的区域，y 轴向下。
+//! 每个像素取 4×4 个采样点计算覆盖率来做抗锯齿，再按覆盖率和颜色的透明度混合。
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::geometry::{segment_distance, Point, Polygon, Rectangle, Shape};
+use crate::kinds::Rgba;
+
+/// 每个方向上的采样数
+const SAMPLES: u32 = 4;
+
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgba>,
+}
+
+/// 把 `src` 按 `coverage` 的比例叠加到 `dst` 上（source-over）
+fn blend(dst: Rgba, src: Rgba, coverage: f64) -> Rgba {
+    let sa = src.a as f64 / 255.0 * coverage;
+    let da = dst.a as f64 / 255.0;
+    let out_a = sa + da * (1.0 - sa);
+    if out_a == 0.0 {
+        return Rgba::TRANSPARENT;
+    }
+    let channel = |s: u8, d: u8| {
+        let c = (s as f64 * sa + d as f64 * da * (1.0 - sa)) / out_a;
+        c.round().clamp(0.0, 255.0) as u8
+    };
+    Rgba::new(
+        channel(src.r, dst.r),
+        channel(src.g, dst.g),
+        channel(src.b, dst.b),
+        (out_a * 255.0).round() as u8,
+    )
+}
+
+impl Canvas {
+    /// 全透明的画布
+    pub fn new(width: u32, height: u32) -> Canvas {
+        Canvas::filled(width, height, Rgba::TRANSPARENT)
+    }
+
+    pub fn filled(width: u32, height: u32, color: Rgba) -> Canvas {
+        Canvas {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Rgba] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Rgba> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.pixels[(y as u64 * self.width as u64 + x as u64) as usize])
+    }
+
+    /// 按覆盖率把颜色混合到一个像素上，超出画布的像素会被忽略
+    pub fn blend_pixel(&mut self, x: i64, y: i64, color: Rgba, coverage: f64) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 || coverage <= 0.0 {
+            return;
+        }
+        let index = (y as u64 * self.width as u64 + x as u64) as usize;
+        self.pixels[index] = blend(self.pixels[index], color, coverage.min(1.0));
+    }
+
+    pub fn clear(&mut self, color: Rgba) {
+        self.pixels.iter_mut().for_each(|p| *p = color);
+    }
+
+    /// 对 `area` 范围内的每个像素计算覆盖率并混合
+    fn paint<F: Fn(&Point<f64>) -> bool>(&mut self, area: Rectangle<f64>, color: Rgba, inside: F) {
+        let x0 = area.min_x().floor().max(0.0) as i64;
+        let y0 = area.min_y().floor().max(0.0) as i64;
+        let x1 = area.max_x().ceil().min(self.width as f64) as i64;
+        let y1 = area.max_y().ceil().min(self.height as f64) as i64;
+        let step = 1.0 / SAMPLES as f64;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let mut hits = 0;
+                for sy in 0..SAMPLES {
+                    for sx in 0..SAMPLES {
+                        let p = Point::new(
+                            x as f64 + (sx as f64 + 0.5) * step,
+                            y as f64 + (sy as f64 + 0.5) * step,
+                        );
+                        if inside(&p) {
+                            hits += 1;
+                        }
+                    }
+                }
+                let coverage = hits as f64 / (SAMPLES * SAMPLES) as f64;
+                self.blend_pixel(x, y, color, coverage);
+            }
+        }
+    }
+
+    /// 填充图形
+    /// ```rust
+    /// use rust_helloworld::canvas::Canvas;
+    /// use rust_helloworld::geometry::{Circle, Point, Rectangle};
+    /// use rust_helloworld::kinds::{PrimaryColor, Rgba};
+    ///
+    /// let mut canvas = Canvas::filled(20, 20, Rgba::WHITE);
+    /// canvas.fill(&Rectangle::new(10.0, 10.0), PrimaryColor::Red.into());
+    /// assert_eq!(canvas.pixel(5, 5), Some(Rgba::rgb(255, 0, 0)));
+    ///
+    /// // 半透明的蓝色叠在红色上
+    /// canvas.fill(&Circle::new(Point::new(5.0, 5.0), 3.0), Rgba::new(0, 0, 255, 128));
+    /// assert_eq!(canvas.pixel(5, 5), Some(Rgba::rgb(127, 0, 128)));
+    ///
+    /// // 圆的边缘是抗锯齿的
+    /// let edge = canvas.pixel(7, 3).unwrap();
+    /// assert!(edge.r > 127 && edge.r < 255);
+    /// ```
+    pub fn fill(&mut self, shape: &dyn Shape, color: Rgba) {
+        self.paint(shape.bounding_box(), color, |p| shape.contains(p));
+    }
+
+    /// 沿图形的轮廓描边，`width` 为线宽
+    pub fn stroke(&mut self, shape: &dyn Shape, width: f64, color: Rgba) {
+        self.stroke_polygon(&shape.to_polygon(), true, width, color);
+    }
+
+    /// 画一条线段
+    pub fn line(&mut self, from: Point<f64>, to: Point<f64>, width: f64, color: Rgba) {
+        self.stroke_polygon(&Polygon::new(vec![from, to]), false, width, color);
+    }
+
+    fn stroke_polygon(&mut self, polygon: &Polygon, closed: bool, width: f64, color: Rgba) {
+        let half = width / 2.0;
+        let bounds = polygon.bounding_box();
+        let area = Rectangle::at(
+            bounds.origin - Point::new(half, half),
+            bounds.width + width,
+            bounds.height + width,
+        );
+        let edges: Vec<(Point<f64>, Point<f64>)> = if closed {
+            polygon.edges().collect()
+        } else {
+            polygon.points.windows(2).map(|w| (w[0], w[1])).collect()
+        };
+        self.paint(area, color, |p| {
+            edges
+                .iter()
+                .any(|(a, b)| segment_distance(*p, *a, *b) <= half)
+        });
+    }
+
+    /// 以二进制 PPM（P6）格式输出，PPM 没有透明通道，会先叠加到白色背景上
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for pixel in &self.pixels {
+            let p = blend(Rgba::WHITE, *pixel, 1.0);
+            data.extend_from_slice(&[p.r, p.g, p.b]);
+        }
+        data
+    }
+
+    /// 以 PNG 格式输出，保留透明通道
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let rgba: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|p| [p.r, p.g, p.b, p.a])
+            .collect();
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&rgba))
+            .map_err(io::Error::other)?;
+        Ok(data)
+    }
+
+    /// 按文件扩展名保存为 `.ppm` 或者 `.png`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let data = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png()?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "only .ppm and .png are supported",
+                ))
+            }
+        };
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(&data)?;
+        file.flush()
+    }
+}
@@ -0,0 +1,738 @@
+//! 成绩册：作业按类别加权计分，按 [`GradePolicy`] 换算成等级，并统计全班、每个学生和
+//! 每份作业的成绩
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use super::{Roster, RosterError};
+use crate::csv;
+use crate::i18n::tr;
+
+/// CSV 的表头，见 [`Gradebook::to_csv`]
+pub const CSV_HEADER: &str = "category,weight,assignment,max_points,student,points";
+
+/// 比较分数线时容忍的浮点误差
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradebookError {
+    Roster(RosterError),
+    UnknownCategory(String),
+    DuplicateCategory(String),
+    UnknownAssignment(u64),
+    DuplicateAssignment(String),
+    UnknownStudent(u64),
+    /// 权重必须是正数
+    InvalidWeight(f64),
+    /// 满分必须是正数
+    InvalidMaxPoints(f64),
+    ScoreOutOfRange {
+        assignment: u64,
+        points: f64,
+        max: f64,
+    },
+    InvalidPolicy(String),
+    /// 字段不是合法的数字
+    InvalidNumber {
+        field: String,
+        value: String,
+    },
+    /// CSV 第 `line` 行有错误，行号从 1 开始
+    Csv {
+        line: usize,
+        message: String,
+    },
+}
+
+impl Display for GradebookError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            GradebookError::Roster(err) => err.to_string(),
+            GradebookError::UnknownCategory(name) => {
+                tr("error.gradebook.unknown_category", &[("name", name)])
+            }
+            GradebookError::DuplicateCategory(name) => {
+                tr("error.gradebook.duplicate_category", &[("name", name)])
+            }
+            GradebookError::UnknownAssignment(id) => {
+                tr("error.gradebook.unknown_assignment", &[("id", id)])
+            }
+            GradebookError::DuplicateAssignment(name) => {
+                tr("error.gradebook.duplicate_assignment", &[("name", name)])
+            }
+            GradebookError::UnknownStudent(id) => {
+                tr("error.gradebook.unknown_student", &[("id", id)])
+            }
+            GradebookError::InvalidWeight(weight) => {
+                tr("error.gradebook.invalid_weight", &[("weight", weight)])
+            }
+            GradebookError::InvalidMaxPoints(max) => {
+                tr("error.gradebook.invalid_max_points", &[("max", max)])
+            }
+            GradebookError::ScoreOutOfRange {
+                assignment,
+                points,
+                max,
+            } => tr(
+                "error.gradebook.score_out_of_range",
+                &[("assignment", assignment), ("points", points), ("max", max)],
+            ),
+            GradebookError::InvalidPolicy(message) => {
+                tr("error.gradebook.invalid_policy", &[("message", message)])
+            }
+            GradebookError::InvalidNumber { field, value } => tr(
+                "error.gradebook.invalid_number",
+                &[("field", field), ("value", value)],
+            ),
+            GradebookError::Csv { line, message } => tr(
+                "error.gradebook.csv",
+                &[("line", line), ("message", message)],
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for GradebookError {}
+
+impl From<RosterError> for GradebookError {
+    fn from(err: RosterError) -> GradebookError {
+        GradebookError::Roster(err)
+    }
+}
+
+/// 从小到大第 `p` 百分位的值，`p` 取 0 到 100，在相邻两个值之间线性插值
+/// ```rust
+/// use rust_helloworld::classroom::percentile;
+///
+/// let values = [15.0, 20.0, 35.0, 40.0, 50.0];
+/// assert_eq!(percentile(&values, 0.0), Some(15.0));
+/// assert_eq!(percentile(&values, 50.0), Some(35.0));
+/// assert_eq!(percentile(&values, 40.0), Some(29.0));
+/// assert_eq!(percentile(&values, 100.0), Some(50.0));
+/// assert_eq!(percentile(&[], 50.0), None);
+/// ```
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64))
+}
+
+/// 一组成绩的统计，标准差按总体计算
+/// ```rust
+/// use rust_helloworld::classroom::Stats;
+///
+/// let stats = Stats::new(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+/// assert_eq!(stats.mean, 5.0);
+/// assert_eq!(stats.median, 4.5);
+/// assert_eq!(stats.std_dev, 2.0);
+/// assert_eq!((stats.min, stats.max), (2.0, 9.0));
+/// assert!(Stats::new(&[]).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    pub fn new(values: &[f64]) -> Option<Stats> {
+        let median = percentile(values, 50.0)?;
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Some(Stats {
+            count: values.len(),
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// 等级规则：从高到低的分数线，百分制成绩达到某条线就得到对应的等级
+/// ```rust
+/// use rust_helloworld::classroom::GradePolicy;
+///
+/// let standard = GradePolicy::standard();
+/// assert_eq!(standard.letter(90.0), "A");
+/// assert_eq!(standard.letter(89.9), "B");
+/// assert_eq!(standard.letter(12.0), "F");
+/// assert_eq!(GradePolicy::plus_minus().letter(88.0), "B+");
+/// assert_eq!(GradePolicy::pass_fail(60.0).letter(60.0), "P");
+///
+/// let policy = GradePolicy::new(&[(85.0, "优"), (60.0, "及格")], "不及格").unwrap();
+/// assert_eq!(policy.letter(70.0), "及格");
+/// assert!(GradePolicy::new(&[(60.0, "P"), (85.0, "A")], "F").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradePolicy {
+    cutoffs: Vec<(f64, String)>,
+    /// 低于所有分数线时的等级
+    fallback: String,
+}
+
+impl GradePolicy {
+    /// 分数线必须在 0 到 100 之间，并且从高到低排列
+    pub fn new(cutoffs: &[(f64, &str)], fallback: &str) -> Result<GradePolicy, GradebookError> {
+        for pair in cutoffs.windows(2) {
+            if pair[0].0 <= pair[1].0 {
+                return Err(GradebookError::InvalidPolicy(tr(
+                    "gradebook.cutoffs_not_descending",
+                    &[],
+                )));
+            }
+        }
+        if let Some(&(cutoff, _)) = cutoffs.iter().find(|(c, _)| !(0.0..=100.0).contains(c)) {
+            return Err(GradebookError::InvalidPolicy(tr(
+                "gradebook.cutoff_out_of_range",
+                &[("cutoff", &cutoff)],
+            )));
+        }
+        Ok(GradePolicy {
+            cutoffs: cutoffs
+                .iter()
+                .map(|&(cutoff, letter)| (cutoff, letter.to_string()))
+                .collect(),
+            fallback: fallback.to_string(),
+        })
+    }
+
+    /// A 90、B 80、C 70、D 60，其余为 F
+    pub fn standard() -> GradePolicy {
+        GradePolicy::new(&[(90.0, "A"), (80.0, "B"), (70.0, "C"), (60.0, "D")], "F")
+            .expect("standard policy is valid")
+    }
+
+    /// 每个等级再分成 +、不带符号和 - 三档
+    pub fn plus_minus() -> GradePolicy {
+        let mut cutoffs = Vec::new();
+        for (base, letter) in [(90.0, "A"), (80.0, "B"), (70.0, "C"), (60.0, "D")] {
+            cutoffs.push((base + 7.0, format!("{}+", letter)));
+            cutoffs.push((base + 3.0, letter.to_string()));
+            cutoffs.push((base, format!("{}-", letter)));
+        }
+        GradePolicy {
+            cutoffs,
+            fallback: "F".to_string(),
+        }
+    }
+
+    /// 达到 `pass` 为 P，否则为 F
+    pub fn pass_fail(pass: f64) -> GradePolicy {
+        GradePolicy {
+            cutoffs: vec![(pass, "P".to_string())],
+            fallback: "F".to_string(),
+        }
+    }
+
+    pub fn letter(&self, percent: f64) -> &str {
+        self.cutoffs
+            .iter()
+            .find(|(cutoff, _)| percent + EPSILON >= *cutoff)
+            .map_or(&self.fallback, |(_, letter)| letter)
+    }
+}
+
+impl Default for GradePolicy {
+    fn default() -> GradePolicy {
+        GradePolicy::standard()
+    }
+}
+
+/// 作业类别，比如作业、测验、考试
+#[derive(Debug, Clone, PartialEq)]
+pub struct Category {
+    pub name: String,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub id: u64,
+    pub name: String,
+    pub category: String,
+    pub max_points: f64,
+}
+
+/// 一个班的成绩册
+///
+/// 类别内按得分之和除以满分之和计算百分比，总成绩是各类别百分比的加权平均。
+/// 学生在某个类别中还没有成绩时，这个类别不参与计算，其余类别的权重按比例放大。
+/// ```rust
+/// use rust_helloworld::classroom::{Gradebook, Roster, Student, Teacher};
+///
+/// let mut roster = Roster::new();
+/// roster.add_teacher(Teacher::new(1, "Ms. Li", "math")).unwrap();
+/// let class = roster.add_class("3A math", 1).unwrap();
+/// for (id, name) in [(10, "Ann"), (11, "Bob"), (12, "Cat")] {
+///     roster.add_student(Student::new(id, name, 3)).unwrap();
+///     roster.enroll(class, id).unwrap();
+/// }
+///
+/// let mut book = Gradebook::for_class(&roster, class).unwrap();
+/// book.add_category("homework", 40.0).unwrap();
+/// book.add_category("exam", 60.0).unwrap();
+/// let hw1 = book.add_assignment("hw1", "homework", 10.0).unwrap();
+/// let hw2 = book.add_assignment("hw2", "homework", 10.0).unwrap();
+/// let final_exam = book.add_assignment("final", "exam", 100.0).unwrap();
+///
+/// for (student, scores) in [(10, [10.0, 9.0, 95.0]), (11, [6.0, 8.0, 70.0]), (12, [8.0, 8.0, 80.0])] {
+///     for (assignment, points) in [hw1, hw2, final_exam].into_iter().zip(scores) {
+///         book.record(assignment, student, points).unwrap();
+///     }
+/// }
+///
+/// // 作业 95% * 0.4 + 考试 95% * 0.6
+/// assert_eq!(book.percent(10), Some(95.0));
+/// assert_eq!(book.letter(10), Some("A"));
+/// assert_eq!(book.letter(11), Some("C"));
+/// assert_eq!(book.class_stats().unwrap().median, 80.0);
+/// assert_eq!(book.percentile_rank(12), Some(50.0));
+/// assert_eq!(book.assignment_stats(final_exam).unwrap().max, 95.0);
+/// assert!(book.record(hw1, 10, 11.0).is_err());
+///
+/// // 导出再导入，成绩不变
+/// let copy = Gradebook::from_csv(&book.to_csv()).unwrap();
+/// assert_eq!(copy.percent(11), book.percent(11));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Gradebook {
+    /// 按添加顺序排列
+    categories: Vec<Category>,
+    assignments: BTreeMap<u64, Assignment>,
+    students: BTreeSet<u64>,
+    /// (作业, 学生) -> 得分
+    scores: BTreeMap<(u64, u64), f64>,
+    policy: GradePolicy,
+    next_assignment_id: u64,
+}
+
+impl Gradebook {
+    pub fn new() -> Gradebook {
+        Gradebook::default()
+    }
+
+    /// 包含班里现有的所有学生，之后入学的学生用 [`Gradebook::add_student`] 加入
+    pub fn for_class(roster: &Roster, class: u64) -> Result<Gradebook, GradebookError> {
+        let class = roster
+            .class(class)
+            .ok_or(RosterError::UnknownClass(class))?;
+        Ok(Gradebook {
+            students: class.students.clone(),
+            ..Gradebook::new()
+        })
+    }
+
+    pub fn add_student(&mut self, student: u64) {
+        self.students.insert(student);
+    }
+
+    /// 按 id 排列
+    pub fn students(&self) -> impl Iterator<Item = u64> + '_ {
+        self.students.iter().copied()
+    }
+
+    pub fn policy(&self) -> &GradePolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: GradePolicy) {
+        self.policy = policy;
+    }
+
+    pub fn add_category(&mut self, name: &str, weight: f64) -> Result<(), GradebookError> {
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(GradebookError::InvalidWeight(weight));
+        }
+        if self.category(name).is_some() {
+            return Err(GradebookError::DuplicateCategory(name.to_string()));
+        }
+        self.categories.push(Category {
+            name: name.to_string(),
+            weight,
+        });
+        Ok(())
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories.iter().find(|c| c.name == name)
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    /// 布置作业，作业名不能重复，返回作业 id
+    pub fn add_assignment(
+        &mut self,
+        name: &str,
+        category: &str,
+        max_points: f64,
+    ) -> Result<u64, GradebookError> {
+        if self.category(category).is_none() {
+            return Err(GradebookError::UnknownCategory(category.to_string()));
+        }
+        if !(max_points.is_finite() && max_points > 0.0) {
+            return Err(GradebookError::InvalidMaxPoints(max_points));
+        }
+        if self.assignments.values().any(|a| a.name == name) {
+            return Err(GradebookError::DuplicateAssignment(name.to_string()));
+        }
+        self.next_assignment_id += 1;
+        let id = self.next_assignment_id;
+        self.assignments.insert(
+            id,
+            Assignment {
+                id,
+                name: name.to_string(),
+                category: category.to_string(),
+                max_points,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn assignment(&self, id: u64) -> Option<&Assignment> {
+        self.assignments.get(&id)
+    }
+
+    /// 按 id 排列
+    pub fn assignments(&self) -> impl Iterator<Item = &Assignment> {
+        self.assignments.values()
+    }
+
+    /// 登记成绩，重复登记时覆盖，得分必须在 0 到满分之间
+    pub fn record(
+        &mut self,
+        assignment: u64,
+        student: u64,
+        points: f64,
+    ) -> Result<(), GradebookError> {
+        let max = self
+            .assignments
+            .get(&assignment)
+            .ok_or(GradebookError::UnknownAssignment(assignment))?
+            .max_points;
+        if !self.students.contains(&student) {
+            return Err(GradebookError::UnknownStudent(student));
+        }
+        if !(0.0..=max).contains(&points) {
+            return Err(GradebookError::ScoreOutOfRange {
+                assignment,
+                points,
+                max,
+            });
+        }
+        self.scores.insert((assignment, student), points);
+        Ok(())
+    }
+
+    pub fn score(&self, assignment: u64, student: u64) -> Option<f64> {
+        self.scores.get(&(assignment, student)).copied()
+    }
+
+    /// 删除成绩，比如作业被免交
+    pub fn remove_score(&mut self, assignment: u64, student: u64) -> Option<f64> {
+        self.scores.remove(&(assignment, student))
+    }
+
+    /// 学生某个类别的百分比成绩，没有成绩时为 None
+    pub fn category_percent(&self, student: u64, category: &str) -> Option<f64> {
+        let (mut points, mut max) = (0.0, 0.0);
+        for assignment in self.assignments.values() {
+            if assignment.category != category {
+                continue;
+            }
+            if let Some(score) = self.score(assignment.id, student) {
+                points += score;
+                max += assignment.max_points;
+            }
+        }
+        (max > 0.0).then(|| points / max * 100.0)
+    }
+
+    /// 学生的百分制总成绩，没有任何成绩时为 None
+    pub fn percent(&self, student: u64) -> Option<f64> {
+        let (mut total, mut weights) = (0.0, 0.0);
+        for category in &self.categories {
+            if let Some(percent) = self.category_percent(student, &category.name) {
+                total += percent * category.weight;
+                weights += category.weight;
+            }
+        }
+        (weights > 0.0).then(|| total / weights)
+    }
+
+    pub fn letter(&self, student: u64) -> Option<&str> {
+        Some(self.policy.letter(self.percent(student)?))
+    }
+
+    /// 每个有成绩的学生的总成绩，按学生 id 排列
+    pub fn percents(&self) -> Vec<(u64, f64)> {
+        self.students
+            .iter()
+            .filter_map(|&id| Some((id, self.percent(id)?)))
+            .collect()
+    }
+
+    /// 全班总成绩的统计
+    pub fn class_stats(&self) -> Option<Stats> {
+        let percents: Vec<f64> = self.percents().into_iter().map(|(_, p)| p).collect();
+        Stats::new(&percents)
+    }
+
+    /// 某份作业的得分率统计，以百分比计
+    pub fn assignment_stats(&self, assignment: u64) -> Option<Stats> {
+        let max = self.assignments.get(&assignment)?.max_points;
+        let percents: Vec<f64> = self
+            .scores
+            .range((assignment, 0)..=(assignment, u64::MAX))
+            .map(|(_, points)| points / max * 100.0)
+            .collect();
+        Stats::new(&percents)
+    }
+
+    /// 学生各份作业得分率的统计，以百分比计
+    pub fn student_stats(&self, student: u64) -> Option<Stats> {
+        let percents: Vec<f64> = self
+            .assignments
+            .values()
+            .filter_map(|a| Some(self.score(a.id, student)? / a.max_points * 100.0))
+            .collect();
+        Stats::new(&percents)
+    }
+
+    /// 百分位排名：总成绩低于该学生的人数加上同分人数的一半，占全班有成绩人数的百分比
+    pub fn percentile_rank(&self, student: u64) -> Option<f64> {
+        let own = self.percent(student)?;
+        let percents = self.percents();
+        let below = percents.iter().filter(|(_, p)| *p < own - EPSILON).count() as f64;
+        let equal = percents
+            .iter()
+            .filter(|(_, p)| (*p - own).abs() <= EPSILON)
+            .count() as f64;
+        Some((below + equal / 2.0) / percents.len() as f64 * 100.0)
+    }
+
+    /// 导出为 CSV，表头见 [`CSV_HEADER`]，每行一个成绩
+    ///
+    /// 没有作业的类别、没有成绩的作业和没有成绩的学生也各占一行，对应的列留空，
+    /// 导入时才能完整地恢复。等级规则不会导出。
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        let mut row = |fields: [String; 6]| {
+            let fields: Vec<String> = fields.iter().map(|f| csv::field(f)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        };
+        let mut graded = BTreeSet::new();
+        for category in &self.categories {
+            let (name, weight) = (category.name.clone(), category.weight.to_string());
+            let assignments: Vec<&Assignment> = self
+                .assignments
+                .values()
+                .filter(|a| a.category == category.name)
+                .collect();
+            if assignments.is_empty() {
+                row([
+                    name.clone(),
+                    weight.clone(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ]);
+            }
+            for assignment in assignments {
+                let (title, max) = (assignment.name.clone(), assignment.max_points.to_string());
+                let scores: Vec<(u64, f64)> = self
+                    .scores
+                    .range((assignment.id, 0)..=(assignment.id, u64::MAX))
+                    .map(|(&(_, student), &points)| (student, points))
+                    .collect();
+                if scores.is_empty() {
+                    row([
+                        name.clone(),
+                        weight.clone(),
+                        title.clone(),
+                        max.clone(),
+                        String::new(),
+                        String::new(),
+                    ]);
+                }
+                for (student, points) in scores {
+                    graded.insert(student);
+                    row([
+                        name.clone(),
+                        weight.clone(),
+                        title.clone(),
+                        max.clone(),
+                        student.to_string(),
+                        points.to_string(),
+                    ]);
+                }
+            }
+        }
+        for student in self.students.difference(&graded) {
+            row([
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                student.to_string(),
+                String::new(),
+            ]);
+        }
+        csv
+    }
+
+    /// 从 [`Gradebook::to_csv`] 的格式导入，作业 id 按出现的顺序重新分配
+    /// ```rust
+    /// use rust_helloworld::classroom::{Gradebook, GradebookError};
+    /// use rust_helloworld::i18n::{set_locale, Locale};
+    ///
+    /// set_locale(Locale::EnUs);
+    /// let csv = "category,weight,assignment,max_points,student,points\n\
+    ///            quiz,1,\"quiz 1, easy\",5,7,4.5\n\
+    ///            ,,,,8,\n";
+    /// let book = Gradebook::from_csv(csv).unwrap();
+    /// assert_eq!(book.students().collect::<Vec<_>>(), vec![7, 8]);
+    /// assert_eq!(book.percent(7), Some(90.0));
+    /// assert_eq!(book.percent(8), None);
+    /// assert!(book.to_csv().contains("\"quiz 1, easy\""));
+    ///
+    /// let csv = "category,weight,assignment,max_points,student,points\nquiz,1,q,5,7,6\n";
+    /// let err = Gradebook::from_csv(csv).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 2: score 6 for assignment #1 out of range 0..=5");
+    ///
+    /// set_locale(Locale::ZhCn);
+    /// let err = Gradebook::from_csv(csv).unwrap_err();
+    /// assert_eq!(err.to_string(), "第 2 行: 作业 #1 的分数 6 超出 0..=5");
+    /// ```
+    pub fn from_csv(text: &str) -> Result<Gradebook, GradebookError> {
+        let mut rows = parse_csv(text)?.into_iter();
+        match rows.next() {
+            Some((_, header)) if header.join(",") == CSV_HEADER => {}
+            _ => {
+                return Err(GradebookError::Csv {
+                    line: 1,
+                    message: tr("gradebook.expected_header", &[("header", &CSV_HEADER)]),
+                })
+            }
+        }
+
+        let mut book = Gradebook::new();
+        for (line, fields) in rows {
+            let csv_error = |message: String| GradebookError::Csv { line, message };
+            let fields = <&[String; 6]>::try_from(fields.as_slice())
+                .map_err(|_| csv_error(tr("gradebook.field_count", &[("found", &fields.len())])))?;
+            book.import_row(fields)
+                .map_err(|err| csv_error(err.to_string()))?;
+        }
+        Ok(book)
+    }
+
+    fn import_row(&mut self, fields: &[String; 6]) -> Result<(), GradebookError> {
+        let [category, weight, assignment, max_points, student, points] = fields;
+        let number = |value: &str, field: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| GradebookError::InvalidNumber {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                })
+        };
+
+        if !category.is_empty() {
+            let weight = number(weight, "weight")?;
+            match self.category(category) {
+                Some(existing) if existing.weight != weight => {
+                    return Err(GradebookError::InvalidWeight(weight))
+                }
+                Some(_) => {}
+                None => self.add_category(category, weight)?,
+            }
+        }
+        let assignment = match assignment.as_str() {
+            "" => None,
+            name => {
+                let max_points = number(max_points, "max_points")?;
+                let existing = self.assignments.values().find(|a| a.name == name);
+                Some(match existing {
+                    Some(a) if a.category == *category && a.max_points == max_points => a.id,
+                    Some(_) => return Err(GradebookError::DuplicateAssignment(name.to_string())),
+                    None => self.add_assignment(name, category, max_points)?,
+                })
+            }
+        };
+        if !student.is_empty() {
+            let id = student.parse().map_err(|_| GradebookError::InvalidNumber {
+                field: "student".to_string(),
+                value: student.to_string(),
+            })?;
+            self.add_student(id);
+            if let (Some(assignment), false) = (assignment, points.is_empty()) {
+                self.record(assignment, id, number(points, "points")?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 解析 CSV，返回每条记录的起始行号和字段，引号中的字段可以包含逗号、引号和换行
+fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, GradebookError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut line, mut start) = (1, 1);
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            (_, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if quoted {
+        return Err(GradebookError::Csv {
+            line: start,
+            message: tr("gradebook.unterminated_quote", &[]),
+        });
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    // 跳过空行
+    records.retain(|(_, fields)| !(fields.len() == 1 && fields[0].is_empty()));
+    Ok(records)
+}
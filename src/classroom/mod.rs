@@ -0,0 +1,340 @@
+//! 班级名册：每个班有一位老师和若干学生，记录选课和每天的出勤，成绩记在 [`Gradebook`] 中
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::i18n::tr;
+
+mod gradebook;
+
+pub use self::gradebook::{
+    percentile, Assignment, Category, GradePolicy, Gradebook, GradebookError, Stats, CSV_HEADER,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// 班级成员打招呼
+pub trait Hello {
+    fn say_hi(&self) -> String {
+        tr("classroom.hi", &[])
+    }
+
+    fn say_something(&self) -> String;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Student {
+    pub id: u64,
+    pub name: String,
+    /// 年级
+    pub grade: u8,
+}
+
+impl Student {
+    pub fn new(id: u64, name: &str, grade: u8) -> Student {
+        Student {
+            id,
+            name: name.to_string(),
+            grade,
+        }
+    }
+}
+
+impl Hello for Student {
+    fn say_something(&self) -> String {
+        tr(
+            "classroom.student",
+            &[("name", &self.name), ("grade", &self.grade)],
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Teacher {
+    pub id: u64,
+    pub name: String,
+    /// 任教的科目
+    pub subject: String,
+}
+
+impl Teacher {
+    pub fn new(id: u64, name: &str, subject: &str) -> Teacher {
+        Teacher {
+            id,
+            name: name.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+}
+
+impl Hello for Teacher {
+    fn say_hi(&self) -> String {
+        tr("classroom.teacher_hi", &[("name", &self.name)])
+    }
+
+    fn say_something(&self) -> String {
+        tr("classroom.teacher", &[("subject", &self.subject)])
+    }
+}
+
+/// 每个成员先打招呼再说一句话，拼成一行
+pub fn greet_all(members: &[&dyn Hello]) -> Vec<String> {
+    members
+        .iter()
+        .map(|member| format!("{} {}", member.say_hi(), member.say_something()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attendance {
+    Present,
+    Late,
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RosterError {
+    UnknownClass(u64),
+    UnknownStudent(u64),
+    UnknownTeacher(u64),
+    /// 同一个 id 已经登记过
+    DuplicateId(u64),
+    AlreadyEnrolled {
+        class: u64,
+        student: u64,
+    },
+    NotEnrolled {
+        class: u64,
+        student: u64,
+    },
+}
+
+impl Display for RosterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            RosterError::UnknownClass(id) => tr("error.roster.unknown_class", &[("id", id)]),
+            RosterError::UnknownStudent(id) => tr("error.roster.unknown_student", &[("id", id)]),
+            RosterError::UnknownTeacher(id) => tr("error.roster.unknown_teacher", &[("id", id)]),
+            RosterError::DuplicateId(id) => tr("error.roster.duplicate_id", &[("id", id)]),
+            RosterError::AlreadyEnrolled { class, student } => tr(
+                "error.roster.already_enrolled",
+                &[("class", class), ("student", student)],
+            ),
+            RosterError::NotEnrolled { class, student } => tr(
+                "error.roster.not_enrolled",
+                &[("class", class), ("student", student)],
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for RosterError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class {
+    pub id: u64,
+    pub name: String,
+    pub teacher: u64,
+    pub students: BTreeSet<u64>,
+    /// 天（unix 天数）-> 学生 -> 出勤
+    attendance: BTreeMap<u64, BTreeMap<u64, Attendance>>,
+}
+
+/// 名册，学生和老师登记一次，可以出现在多个班里
+/// ```rust
+/// use rust_helloworld::classroom::{Attendance, Roster, RosterError, Student, Teacher};
+/// use rust_helloworld::i18n::{set_locale, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// let mut roster = Roster::new();
+/// roster.add_teacher(Teacher::new(1, "Ms. Li", "math")).unwrap();
+/// roster.add_student(Student::new(10, "Ann", 3)).unwrap();
+/// roster.add_student(Student::new(11, "Bob", 3)).unwrap();
+///
+/// let class = roster.add_class("3A math", 1).unwrap();
+/// roster.enroll(class, 10).unwrap();
+/// roster.enroll(class, 11).unwrap();
+/// assert_eq!(
+///     roster.enroll(class, 10),
+///     Err(RosterError::AlreadyEnrolled { class, student: 10 })
+/// );
+///
+/// assert_eq!(
+///     roster.greet_all(class).unwrap(),
+///     vec![
+///         "Hi, I'm Ms. Li, your new teacher. I teach math, and I'm not a bad teacher",
+///         "hi I'm Ann, a good student in grade 3",
+///         "hi I'm Bob, a good student in grade 3",
+///     ]
+/// );
+///
+/// let day = 1_700_000_000;
+/// roster.mark(class, 10, day, Attendance::Present).unwrap();
+/// roster.mark(class, 11, day + 60, Attendance::Late).unwrap();
+/// roster.mark(class, 11, day + 86_400, Attendance::Absent).unwrap();
+/// assert_eq!(roster.attendance(class, day).unwrap(), vec![(10, Attendance::Present), (11, Attendance::Late)]);
+/// assert_eq!(roster.attendance_rate(class, 11).unwrap(), Some(0.5));
+///
+/// assert_eq!(roster.enroll(class, 99).unwrap_err().to_string(), "student #99 not found");
+/// set_locale(Locale::ZhCn);
+/// assert_eq!(roster.enroll(class, 99).unwrap_err().to_string(), "找不到学生 #99");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    students: BTreeMap<u64, Student>,
+    teachers: BTreeMap<u64, Teacher>,
+    classes: BTreeMap<u64, Class>,
+    next_class_id: u64,
+}
+
+impl Roster {
+    pub fn new() -> Roster {
+        Roster::default()
+    }
+
+    pub fn add_student(&mut self, student: Student) -> Result<(), RosterError> {
+        if self.students.contains_key(&student.id) {
+            return Err(RosterError::DuplicateId(student.id));
+        }
+        self.students.insert(student.id, student);
+        Ok(())
+    }
+
+    pub fn add_teacher(&mut self, teacher: Teacher) -> Result<(), RosterError> {
+        if self.teachers.contains_key(&teacher.id) {
+            return Err(RosterError::DuplicateId(teacher.id));
+        }
+        self.teachers.insert(teacher.id, teacher);
+        Ok(())
+    }
+
+    pub fn student(&self, id: u64) -> Option<&Student> {
+        self.students.get(&id)
+    }
+
+    pub fn teacher(&self, id: u64) -> Option<&Teacher> {
+        self.teachers.get(&id)
+    }
+
+    pub fn class(&self, id: u64) -> Option<&Class> {
+        self.classes.get(&id)
+    }
+
+    /// 按 id 排列
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        self.classes.values()
+    }
+
+    /// 开一个班，返回班级 id
+    pub fn add_class(&mut self, name: &str, teacher: u64) -> Result<u64, RosterError> {
+        if !self.teachers.contains_key(&teacher) {
+            return Err(RosterError::UnknownTeacher(teacher));
+        }
+        self.next_class_id += 1;
+        let id = self.next_class_id;
+        self.classes.insert(
+            id,
+            Class {
+                id,
+                name: name.to_string(),
+                teacher,
+                students: BTreeSet::new(),
+                attendance: BTreeMap::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    fn class_mut(&mut self, id: u64) -> Result<&mut Class, RosterError> {
+        self.classes
+            .get_mut(&id)
+            .ok_or(RosterError::UnknownClass(id))
+    }
+
+    fn class_ref(&self, id: u64) -> Result<&Class, RosterError> {
+        self.classes.get(&id).ok_or(RosterError::UnknownClass(id))
+    }
+
+    pub fn enroll(&mut self, class: u64, student: u64) -> Result<(), RosterError> {
+        if !self.students.contains_key(&student) {
+            return Err(RosterError::UnknownStudent(student));
+        }
+        if !self.class_mut(class)?.students.insert(student) {
+            return Err(RosterError::AlreadyEnrolled { class, student });
+        }
+        Ok(())
+    }
+
+    /// 退课，出勤记录保留
+    pub fn withdraw(&mut self, class: u64, student: u64) -> Result<(), RosterError> {
+        if !self.class_mut(class)?.students.remove(&student) {
+            return Err(RosterError::NotEnrolled { class, student });
+        }
+        Ok(())
+    }
+
+    /// 老师在前，学生按 id 排列
+    pub fn members(&self, class: u64) -> Result<Vec<&dyn Hello>, RosterError> {
+        let class = self.class_ref(class)?;
+        let mut members: Vec<&dyn Hello> = Vec::new();
+        if let Some(teacher) = self.teachers.get(&class.teacher) {
+            members.push(teacher);
+        }
+        for id in &class.students {
+            if let Some(student) = self.students.get(id) {
+                members.push(student);
+            }
+        }
+        Ok(members)
+    }
+
+    /// 全班依次打招呼，见 [`greet_all`]
+    pub fn greet_all(&self, class: u64) -> Result<Vec<String>, RosterError> {
+        Ok(greet_all(&self.members(class)?))
+    }
+
+    /// 记录 `at`（unix 秒）那一天的出勤，同一天重复记录时以最后一次为准
+    pub fn mark(
+        &mut self,
+        class: u64,
+        student: u64,
+        at: u64,
+        attendance: Attendance,
+    ) -> Result<(), RosterError> {
+        let class_ref = self.class_mut(class)?;
+        if !class_ref.students.contains(&student) {
+            return Err(RosterError::NotEnrolled { class, student });
+        }
+        class_ref
+            .attendance
+            .entry(at / SECONDS_PER_DAY)
+            .or_default()
+            .insert(student, attendance);
+        Ok(())
+    }
+
+    /// `at` 那一天记录过的出勤，按学生 id 排列
+    pub fn attendance(&self, class: u64, at: u64) -> Result<Vec<(u64, Attendance)>, RosterError> {
+        let class = self.class_ref(class)?;
+        Ok(class
+            .attendance
+            .get(&(at / SECONDS_PER_DAY))
+            .map(|day| day.iter().map(|(&id, &a)| (id, a)).collect())
+            .unwrap_or_default())
+    }
+
+    /// 出勤率，迟到也算出勤；没有任何记录时为 None
+    pub fn attendance_rate(&self, class: u64, student: u64) -> Result<Option<f64>, RosterError> {
+        let class = self.class_ref(class)?;
+        let records: Vec<Attendance> = class
+            .attendance
+            .values()
+            .filter_map(|day| day.get(&student).copied())
+            .collect();
+        if records.is_empty() {
+            return Ok(None);
+        }
+        let attended = records.iter().filter(|&&a| a != Attendance::Absent).count();
+        Ok(Some(attended as f64 / records.len() as f64))
+    }
+}
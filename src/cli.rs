@@ -0,0 +1,276 @@
+//! 给前台和设计师用的命令行
+//!
+//! ```text
+//! waitlist add <name> <size>     加入候位
+//! waitlist [list]                查看候位名单
+//! seat <party_id> <table>        入座
+//! order <table> <covers> <name:category:price[:quantity]>...
+//!                                点单，价格单位为分
+//! orders                         查看订单
+//! pay <order_id> [tip]           收款
+//! report [csv|json]              当天的 Z 报表，缺省为 JSON
+//! palette mix <color> <color>    调色，颜色为 red / yellow / blue
+//! repl                           进入交互模式
+//! ```
+//!
+//! 数据保存在环境变量 `ART_DATA` 指定的文件中，缺省为 `restaurant.jsonl`；
+//! 操作人取自 `ART_ACTOR`，缺省为 `cli`；输出的语言取自 `ART_LOCALE`，见 [`crate::i18n`]。
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead, Write};
+
+use crate::front_of_house::hosting;
+use crate::front_of_house::serving::{self, OrderItem};
+use crate::i18n::{tr, tr_count};
+use crate::kinds::PrimaryColor;
+use crate::report;
+use crate::storage::{JsonLinesStorage, MemoryStorage, Storage, StorageError};
+use crate::utils::{now, try_mix};
+
+/// 当前语言的用法说明
+pub fn help() -> String {
+    tr("cli.usage", &[])
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    /// 命令或参数不正确
+    Usage(String),
+    Storage(StorageError),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Storage(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<StorageError> for CliError {
+    fn from(err: StorageError) -> CliError {
+        CliError::Storage(err)
+    }
+}
+
+fn usage(message: &str) -> CliError {
+    CliError::Usage(message.to_string())
+}
+
+fn number<T: std::str::FromStr>(arg: &str, name: &str) -> Result<T, CliError> {
+    arg.parse()
+        .map_err(|_| usage(&tr("cli.invalid_arg", &[("name", &name), ("arg", &arg)])))
+}
+
+fn color(arg: &str) -> Result<PrimaryColor, CliError> {
+    match arg.to_lowercase().as_str() {
+        "red" => Ok(PrimaryColor::Red),
+        "yellow" => Ok(PrimaryColor::Yellow),
+        "blue" => Ok(PrimaryColor::Blue),
+        _ => Err(usage(&tr("cli.unknown_color", &[("arg", &arg)]))),
+    }
+}
+
+fn item(arg: &str) -> Result<OrderItem, CliError> {
+    let parts: Vec<&str> = arg.split(':').collect();
+    if parts.len() < 3 || parts.len() > 4 {
+        return Err(usage(&tr("cli.invalid_item", &[("arg", &arg)])));
+    }
+    let price = number(parts[2], "price")?;
+    let quantity = match parts.get(3) {
+        Some(quantity) => number(quantity, "quantity")?,
+        None => 1,
+    };
+    Ok(OrderItem::new(parts[0], parts[1], price, quantity))
+}
+
+/// 执行一条命令，返回要输出的文本
+/// ```rust
+/// use rust_helloworld::cli::execute;
+/// use rust_helloworld::storage::MemoryStorage;
+///
+/// let mut store = MemoryStorage::new();
+/// assert_eq!(execute(&mut store, "cli", &["palette", "mix", "red", "yellow"]).unwrap(), "Orange");
+///
+/// execute(&mut store, "cli", &["waitlist", "add", "Ann", "2"]).unwrap();
+/// let output = execute(&mut store, "cli", &["waitlist"]).unwrap();
+/// assert!(output.contains("Ann"));
+///
+/// assert!(execute(&mut store, "cli", &["seat", "abc", "1"]).is_err());
+/// ```
+pub fn execute(store: &mut dyn Storage, actor: &str, args: &[&str]) -> Result<String, CliError> {
+    match args {
+        ["waitlist", "add", name, size] => {
+            let party = hosting::add_to_waitlist(store, name, number(size, "size")?)?;
+            Ok(party_line("cli.party_added", &party))
+        }
+        ["waitlist"] | ["waitlist", "list"] => {
+            let lines: Vec<String> = store
+                .waitlist()?
+                .iter()
+                .map(|party| party_line("cli.party", party))
+                .collect();
+            Ok(lines.join("\n"))
+        }
+        ["seat", party, table] => {
+            let party = number(party, "party_id")?;
+            match hosting::seat_at_table(store, party, number(table, "table")?)? {
+                Some(seating) => Ok(tr(
+                    "cli.seated",
+                    &[
+                        ("name", &seating.party.name),
+                        ("table", &seating.table),
+                        ("greeting", &seating.greeting()),
+                    ],
+                )),
+                None => Err(usage(&tr("cli.not_waiting", &[("id", &party)]))),
+            }
+        }
+        ["order", table, covers, items @ ..] if !items.is_empty() => {
+            let items = items
+                .iter()
+                .map(|arg| item(arg))
+                .collect::<Result<Vec<_>, _>>()?;
+            let table = number(table, "table")?;
+            let covers = number(covers, "covers")?;
+            let order = serving::take_order(store, actor, table, covers, items)?;
+            Ok(tr(
+                "cli.order_taken",
+                &[("id", &order.id), ("total", &order.total())],
+            ))
+        }
+        ["orders"] => {
+            let lines: Vec<String> = store
+                .orders()?
+                .iter()
+                .map(|order| {
+                    let status = order.status.label();
+                    tr(
+                        "cli.order",
+                        &[
+                            ("id", &order.id),
+                            ("table", &order.table),
+                            ("status", &status),
+                            ("total", &order.total()),
+                        ],
+                    )
+                })
+                .collect();
+            Ok(lines.join("\n"))
+        }
+        ["pay", order] => pay(store, actor, order, "0"),
+        ["pay", order, tip] => pay(store, actor, order, tip),
+        ["report"] | ["report", "json"] => Ok(report::z_report(store, now())?.to_json()),
+        ["report", "csv"] => Ok(report::z_report(store, now())?.to_csv()),
+        ["palette", "mix", c1, c2] => match try_mix(color(c1)?, color(c2)?) {
+            Some(color) => Ok(format!("{:?}", color)),
+            None => Err(usage(&tr("cli.same_color", &[]))),
+        },
+        ["help"] => Ok(help()),
+        _ => Err(usage(&help())),
+    }
+}
+
+fn pay(store: &mut dyn Storage, actor: &str, order: &str, tip: &str) -> Result<String, CliError> {
+    let order = number(order, "order_id")?;
+    match serving::take_payment(store, actor, order, number(tip, "tip")?)? {
+        Some(payment) => Ok(tr(
+            "cli.payment",
+            &[
+                ("id", &payment.id),
+                ("amount", &payment.amount),
+                ("tip", &payment.tip),
+            ],
+        )),
+        None => Err(usage(&tr("cli.order_not_found", &[("id", &order)]))),
+    }
+}
+
+/// 候位的一桌客人，人数区分单复数
+fn party_line(key: &str, party: &hosting::Party) -> String {
+    tr_count(
+        key,
+        party.size as u64,
+        &[("id", &party.id), ("name", &party.name)],
+    )
+}
+
+/// 交互模式：逐行读取命令执行，直到输入结束或者 `quit`
+pub fn repl<R: BufRead, W: Write>(
+    store: &mut dyn Storage,
+    actor: &str,
+    input: R,
+    mut output: W,
+) -> io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.as_slice() {
+            [] => {}
+            ["quit"] | ["exit"] => break,
+            args => match execute(store, actor, args) {
+                Ok(text) => writeln!(output, "{}", text)?,
+                Err(err) => writeln!(output, "{}", tr("cli.error", &[("error", &err)]))?,
+            },
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// 是否需要读写餐厅的数据，`help`、`palette` 等命令不会创建数据文件
+fn needs_storage(args: &[&str]) -> bool {
+    matches!(
+        args.first(),
+        Some(&("waitlist" | "seat" | "order" | "orders" | "pay" | "report" | "repl"))
+    )
+}
+
+/// 命令行入口，返回进程退出码
+pub fn run(args: &[String]) -> i32 {
+    let actor = env::var("ART_ACTOR").unwrap_or_else(|_| String::from("cli"));
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut store: Box<dyn Storage> = if needs_storage(&args) {
+        let path = env::var("ART_DATA").unwrap_or_else(|_| String::from("restaurant.jsonl"));
+        match JsonLinesStorage::open(&path) {
+            Ok(store) => Box::new(store),
+            Err(err) => {
+                eprintln!("{}", err);
+                return 1;
+            }
+        }
+    } else {
+        // 这些命令不会访问存储
+        Box::new(MemoryStorage::new())
+    };
+
+    if args == ["repl"] {
+        let stdin = io::stdin();
+        return match repl(store.as_mut(), &actor, stdin.lock(), io::stdout()) {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("{}", err);
+                1
+            }
+        };
+    }
+    match execute(store.as_mut(), &actor, &args) {
+        Ok(text) => {
+            println!("{}", text);
+            0
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            match err {
+                CliError::Usage(_) => 2,
+                CliError::Storage(_) => 1,
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! 内容发布：长文 [`Post`]、短消息 [`Tweet`]，按时间倒序聚合内容的 [`Feed`]，
+//! 以及全文检索 [`SearchIndex`]
+use std::fmt::{Display, Formatter};
+
+use crate::i18n::tr;
+use crate::utils::now;
+
+mod render;
+mod search;
+mod summary;
+
+pub use self::render::{Html, Markdown, PlainText, Render};
+pub use self::search::{Hit, SearchIndex, Searchable};
+pub use self::summary::{extract, sentences, truncate, Limit};
+
+/// 短消息的最大字符数
+pub const TWEET_MAX_CHARS: usize = 280;
+/// [`Post`] 摘要的长度
+pub const SUMMARY_LIMIT: Limit = Limit::Graphemes(80);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentError {
+    /// 内容超过了长度限制
+    TooLong { limit: usize, actual: usize },
+    /// 内容为空
+    Empty,
+}
+
+impl Display for ContentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ContentError::TooLong { limit, actual } => tr(
+                "error.content.too_long",
+                &[("actual", actual), ("limit", limit)],
+            ),
+            ContentError::Empty => tr("error.content.empty", &[]),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+/// 可以出现在 [`Feed`] 里的内容
+pub trait Summary {
+    fn summarize(&self) -> String;
+
+    fn default_summary(&self) -> String {
+        tr("summary.read_more", &[])
+    }
+
+    /// 发布时间，尚未发布时为 None，不会出现在 [`Feed`] 里
+    fn published_at(&self) -> Option<u64>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostStatus {
+    Draft,
+    Published { at: u64 },
+}
+
+/// 长文，新建时是草稿
+/// ```rust
+/// use rust_helloworld::content::{Post, PostStatus, Summary};
+/// use rust_helloworld::i18n::{set_locale, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// let mut post = Post::new(1, "Rust 语言介绍", "Sunface", "Rust 棒极了");
+/// post.tag("Rust");
+/// post.tag("rust");
+/// assert_eq!(post.tags, vec!["rust"]);
+/// assert_eq!(post.published_at(), None);
+///
+/// post.publish_at(1_700_000_000);
+/// assert_eq!(post.status, PostStatus::Published { at: 1_700_000_000 });
+/// assert_eq!(post.summarize(), "Rust 语言介绍 by Sunface: Rust 棒极了");
+///
+/// // 摘要使用当前语言
+/// set_locale(Locale::ZhCn);
+/// assert_eq!(post.summarize(), "文章是Rust 语言介绍, 作者是Sunface: Rust 棒极了");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Post {
+    pub id: u64,
+    pub title: String,
+    pub author: String,
+    pub content: String,
+    /// 小写，不重复，按添加顺序排列
+    pub tags: Vec<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub status: PostStatus,
+}
+
+impl Post {
+    pub fn new(id: u64, title: &str, author: &str, content: &str) -> Post {
+        let time = now();
+        Post {
+            id,
+            title: title.to_string(),
+            author: author.to_string(),
+            content: content.to_string(),
+            tags: Vec::new(),
+            created_at: time,
+            updated_at: time,
+            status: PostStatus::Draft,
+        }
+    }
+
+    /// 添加标签，标签统一转成小写，已有的标签不会重复添加
+    pub fn tag(&mut self, tag: &str) {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag.trim().to_lowercase())
+    }
+
+    pub fn edit(&mut self, content: &str) {
+        self.content = content.to_string();
+        self.updated_at = now();
+    }
+
+    pub fn publish(&mut self) {
+        self.publish_at(now());
+    }
+
+    /// 定时发布，已经发布的文章保持原来的发布时间
+    pub fn publish_at(&mut self, time: u64) {
+        if self.status == PostStatus::Draft {
+            self.status = PostStatus::Published { at: time };
+        }
+    }
+
+    /// 撤回为草稿
+    pub fn unpublish(&mut self) {
+        self.status = PostStatus::Draft;
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.status != PostStatus::Draft
+    }
+
+    /// 正文的摘要，见 [`extract`]
+    pub fn excerpt(&self, limit: Limit) -> String {
+        extract(&self.content, limit)
+    }
+
+    /// 按指定的格式输出
+    /// ```rust
+    /// use rust_helloworld::content::{Html, Markdown, Post};
+    ///
+    /// let post = Post::new(1, "Rust", "Sunface", "Rust 棒极了");
+    /// assert!(post.render(&Markdown).starts_with("# Rust\n"));
+    /// assert!(post.render(&Html).starts_with("<article>"));
+    /// ```
+    pub fn render(&self, format: &dyn Render) -> String {
+        format.render(self)
+    }
+}
+
+impl Display for Post {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&PlainText.render(self))
+    }
+}
+
+impl Summary for Post {
+    /// 标题、作者和从正文中抽取的摘要
+    fn summarize(&self) -> String {
+        tr(
+            "post.summary",
+            &[
+                ("title", &self.title),
+                ("author", &self.author),
+                ("excerpt", &self.excerpt(SUMMARY_LIMIT)),
+            ],
+        )
+    }
+
+    fn published_at(&self) -> Option<u64> {
+        match self.status {
+            PostStatus::Draft => None,
+            PostStatus::Published { at } => Some(at),
+        }
+    }
+}
+
+/// 短消息，创建即发布
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tweet {
+    pub id: u64,
+    pub username: String,
+    pub content: String,
+    /// 回复的消息
+    pub reply_to: Option<u64>,
+    pub created_at: u64,
+}
+
+impl Tweet {
+    /// 内容不能为空，也不能超过 [`TWEET_MAX_CHARS`] 个字符
+    pub fn new(id: u64, username: &str, content: &str) -> Result<Tweet, ContentError> {
+        Tweet::at(id, username, content, now())
+    }
+
+    pub fn at(id: u64, username: &str, content: &str, time: u64) -> Result<Tweet, ContentError> {
+        let chars = content.chars().count();
+        if content.trim().is_empty() {
+            return Err(ContentError::Empty);
+        }
+        if chars > TWEET_MAX_CHARS {
+            return Err(ContentError::TooLong {
+                limit: TWEET_MAX_CHARS,
+                actual: chars,
+            });
+        }
+        Ok(Tweet {
+            id,
+            username: username.to_string(),
+            content: content.to_string(),
+            reply_to: None,
+            created_at: time,
+        })
+    }
+
+    pub fn reply_to(mut self, id: u64) -> Tweet {
+        self.reply_to = Some(id);
+        self
+    }
+}
+
+impl Summary for Tweet {
+    fn summarize(&self) -> String {
+        format!("@{}: {}", self.username, self.content)
+    }
+
+    fn published_at(&self) -> Option<u64> {
+        Some(self.created_at)
+    }
+}
+
+impl Display for Tweet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}: {}", self.username, self.content)
+    }
+}
+
+/// 聚合各种内容，按发布时间从新到旧排列，未发布的内容不会出现
+/// ```rust
+/// use rust_helloworld::content::{Feed, Post, Tweet};
+/// use rust_helloworld::i18n::{set_locale, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// let mut post = Post::new(1, "Rust 语言介绍", "Sunface", "Rust 棒极了");
+/// post.publish_at(100);
+/// let draft = Post::new(2, "草稿", "Sunface", "还没写完");
+///
+/// let mut feed = Feed::new();
+/// feed.push(post);
+/// feed.push(draft);
+/// feed.push(Tweet::at(3, "rustlang", "Rust 1.0 发布了", 200).unwrap());
+/// feed.push(Tweet::at(4, "ferris", "🦀", 50).unwrap());
+///
+/// assert_eq!(feed.len(), 3);
+/// assert_eq!(
+///     feed.summaries(),
+///     vec!["@rustlang: Rust 1.0 发布了", "Rust 语言介绍 by Sunface: Rust 棒极了", "@ferris: 🦀"]
+/// );
+/// assert_eq!(feed.page(1, 1)[0].published_at(), Some(100));
+/// ```
+#[derive(Default)]
+pub struct Feed {
+    items: Vec<Box<dyn Summary>>,
+}
+
+impl Feed {
+    pub fn new() -> Feed {
+        Feed { items: Vec::new() }
+    }
+
+    pub fn push<S: Summary + 'static>(&mut self, item: S) {
+        self.items.push(Box::new(item));
+    }
+
+    /// 已发布的内容数
+    pub fn len(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.published_at().is_some())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 已发布的内容，从新到旧，发布时间相同时后加入的在前
+    pub fn entries(&self) -> Vec<&dyn Summary> {
+        let mut entries: Vec<(u64, usize, &dyn Summary)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| Some((item.published_at()?, i, item.as_ref())))
+            .collect();
+        entries.sort_by_key(|&(at, i, _)| std::cmp::Reverse((at, i)));
+        entries.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// 分页，`offset` 从 0 开始
+    pub fn page(&self, offset: usize, limit: usize) -> Vec<&dyn Summary> {
+        self.entries()
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect()
+    }
+
+    pub fn summaries(&self) -> Vec<String> {
+        self.entries().iter().map(|item| item.summarize()).collect()
+    }
+}
@@ -0,0 +1,167 @@
+//! 把 [`Post`] 输出为不同的格式，实现 [`Render`] 就可以增加新的格式
+//!
+//! 作者、标签等标注使用当前语言，见 [`crate::i18n`]
+use super::Post;
+use crate::i18n::tr;
+
+/// 文章的输出格式
+pub trait Render {
+    fn render(&self, post: &Post) -> String;
+}
+
+/// 纯文本，也是 `Post` 的 `Display` 格式
+/// ```rust
+/// use rust_helloworld::content::{PlainText, Post, Render};
+/// use rust_helloworld::i18n::{set_locale, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// let mut post = Post::new(1, "Rust 语言介绍", "Sunface", "Rust 棒极了");
+/// post.tag("rust");
+/// assert_eq!(PlainText.render(&post), "Rust 语言介绍\nAuthor: Sunface\nTags: rust\n\nRust 棒极了");
+/// assert_eq!(post.to_string(), PlainText.render(&post));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainText;
+
+/// Markdown，标题、作者和标签中的 Markdown 符号会被转义，正文原样输出
+///
+/// 标签放在代码中，反引号里的反斜杠不起作用，所以含有 `` ` ``、`<`、`>` 或 `&` 的标签
+/// 改用 `<code>` 标签并转义成 HTML 实体。正文里的代码和代码块由 Markdown 渲染器
+/// 原样显示，不需要转义。
+/// ```rust
+/// use rust_helloworld::content::{Markdown, Post, Render};
+/// use rust_helloworld::i18n::{set_locale, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// let mut post = Post::new(1, "*Rust* 语言介绍", "Sunface", "Rust **棒极了**");
+/// post.tag("rust");
+/// post.tag("web");
+/// assert_eq!(
+///     Markdown.render(&post),
+///     "# \\*Rust\\* 语言介绍\n\n*Author: Sunface* · `rust` `web`\n\nRust **棒极了**\n"
+/// );
+///
+/// let mut post = Post::new(1, "t", "a", "`<b>` & b");
+/// post.tag("<b>&`");
+/// assert_eq!(
+///     Markdown.render(&post),
+///     "# t\n\n*Author: a* · <code>&lt;b&gt;&amp;`</code>\n\n`<b>` & b\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Markdown;
+
+/// HTML，所有文本都会被转义，正文按空行分段
+/// ```rust
+/// use rust_helloworld::content::{Html, Post, Render};
+///
+/// let post = Post::new(1, "<script>", "Tom & Jerry", "第一段\n\n\"第二段\"");
+/// assert_eq!(
+///     Html.render(&post),
+///     "<article>\n  <h1>&lt;script&gt;</h1>\n  <p class=\"author\">Tom &amp; Jerry</p>\n  \
+///      <p>第一段</p>\n  <p>&quot;第二段&quot;</p>\n</article>"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Html;
+
+impl Render for PlainText {
+    fn render(&self, post: &Post) -> String {
+        let mut s = format!(
+            "{}\n{}\n",
+            post.title,
+            tr("post.author", &[("author", &post.author)])
+        );
+        if !post.tags.is_empty() {
+            let tags = post.tags.join(", ");
+            s.push_str(&format!("{}\n", tr("post.tags", &[("tags", &tags)])));
+        }
+        s.push('\n');
+        s.push_str(&post.content);
+        s
+    }
+}
+
+/// 转义行内的 Markdown 符号
+fn escape_markdown(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '#' | '[' | ']' | '<' | '>') {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
+}
+
+/// 标签输出为代码，见 [`Markdown`]
+fn markdown_code(text: &str) -> String {
+    if text.contains(['`', '<', '>', '&']) {
+        format!("<code>{}</code>", escape_html(text))
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+impl Render for Markdown {
+    fn render(&self, post: &Post) -> String {
+        let author = escape_markdown(&post.author);
+        let mut s = format!(
+            "# {}\n\n*{}*",
+            escape_markdown(&post.title),
+            tr("post.author", &[("author", &author)])
+        );
+        if !post.tags.is_empty() {
+            let tags: Vec<String> = post.tags.iter().map(|t| markdown_code(t)).collect();
+            s.push_str(&format!(" · {}", tags.join(" ")));
+        }
+        s.push_str("\n\n");
+        s.push_str(post.content.trim_end());
+        s.push('\n');
+        s
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&#39;"),
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+impl Render for Html {
+    fn render(&self, post: &Post) -> String {
+        let mut s = String::from("<article>\n");
+        s.push_str(&format!("  <h1>{}</h1>\n", escape_html(&post.title)));
+        s.push_str(&format!(
+            "  <p class=\"author\">{}</p>\n",
+            escape_html(&post.author)
+        ));
+        if !post.tags.is_empty() {
+            let tags: Vec<String> = post
+                .tags
+                .iter()
+                .map(|t| format!("<li>{}</li>", escape_html(t)))
+                .collect();
+            s.push_str(&format!("  <ul class=\"tags\">{}</ul>\n", tags.concat()));
+        }
+        // 段落内的换行输出为 <br>
+        for paragraph in post.content.split("\n\n").map(str::trim) {
+            if paragraph.is_empty() {
+                continue;
+            }
+            let lines: Vec<String> = paragraph.lines().map(escape_html).collect();
+            s.push_str(&format!("  <p>{}</p>\n", lines.join("<br>")));
+        }
+        s.push_str("</article>");
+        s
+    }
+}
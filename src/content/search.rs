@@ -0,0 +1,307 @@
+//! 全文检索：倒排索引记录每个词在每篇内容中出现的位置，按 BM25 排序
+//!
+//! 英文按单词切分并转成小写。中文、日文、韩文逐字占一个位置，索引时同时记录单字和
+//! 相邻两个字组成的词；查询时连续两个以上的字按相邻两字匹配，单独一个字按单字匹配。
+//! 用双引号括起来的短语要求词按顺序紧挨着出现。
+use std::collections::{BTreeMap, HashMap};
+
+use super::summary::is_cjk;
+use super::{Post, Tweet};
+
+/// BM25 的词频饱和参数
+const K1: f64 = 1.2;
+/// BM25 的长度归一化参数
+const B: f64 = 0.75;
+
+/// 可以被索引的内容
+pub trait Searchable {
+    /// 在索引中唯一标识这条内容
+    fn search_id(&self) -> u64;
+
+    /// 参与检索的全部文字，不同字段之间用换行隔开
+    fn search_text(&self) -> String;
+}
+
+impl Searchable for Post {
+    fn search_id(&self) -> u64 {
+        self.id
+    }
+
+    /// 标题、标签和正文
+    fn search_text(&self) -> String {
+        format!("{}\n{}\n{}", self.title, self.tags.join(" "), self.content)
+    }
+}
+
+impl Searchable for Tweet {
+    fn search_id(&self) -> u64 {
+        self.id
+    }
+
+    fn search_text(&self) -> String {
+        format!("{}\n{}", self.username, self.content)
+    }
+}
+
+/// 切词，返回词和它的位置
+///
+/// `index` 为 true 时汉字同时输出单字和相邻两字，否则只在单独一个字时输出单字。
+fn tokenize(text: &str, index: bool) -> Vec<(String, u32)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut word = String::new();
+    let mut run: Vec<char> = Vec::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<(String, u32)>, pos: &mut u32| {
+        if !word.is_empty() {
+            tokens.push((std::mem::take(word), *pos));
+            *pos += 1;
+        }
+    };
+    let flush_run = |run: &mut Vec<char>, tokens: &mut Vec<(String, u32)>, pos: &mut u32| {
+        for (i, &c) in run.iter().enumerate() {
+            let at = *pos + i as u32;
+            if index || run.len() == 1 {
+                tokens.push((c.to_string(), at));
+            }
+            if let Some(&next) = run.get(i + 1) {
+                tokens.push(([c, next].iter().collect(), at));
+            }
+        }
+        *pos += run.len() as u32;
+        run.clear();
+    };
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens, &mut pos);
+            run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_run(&mut run, &mut tokens, &mut pos);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_run(&mut run, &mut tokens, &mut pos);
+            flush_word(&mut word, &mut tokens, &mut pos);
+        }
+    }
+    flush_run(&mut run, &mut tokens, &mut pos);
+    flush_word(&mut word, &mut tokens, &mut pos);
+    tokens
+}
+
+/// 解析后的查询：散列的词和短语
+struct Query {
+    terms: Vec<String>,
+    /// 每个短语是词和它在短语中的位置
+    phrases: Vec<Vec<(String, u32)>>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Query {
+        let mut terms = Vec::new();
+        let mut phrases = Vec::new();
+        // 引号之间是短语，没有闭合的引号按普通的词处理
+        let parts: Vec<&str> = query.split('"').collect();
+        let closed = parts.len() % 2 == 1;
+        for (i, part) in parts.iter().enumerate() {
+            let tokens = tokenize(part, false);
+            let is_phrase = i % 2 == 1 && (closed || i + 1 < parts.len());
+            if is_phrase && !tokens.is_empty() {
+                phrases.push(tokens);
+            } else {
+                terms.extend(tokens.into_iter().map(|(term, _)| term));
+            }
+        }
+        Query { terms, phrases }
+    }
+
+    fn all_terms(&self) -> Vec<&str> {
+        let mut all: Vec<&str> = self.terms.iter().map(String::as_str).collect();
+        for phrase in &self.phrases {
+            all.extend(phrase.iter().map(|(term, _)| term.as_str()));
+        }
+        all.sort_unstable();
+        all.dedup();
+        all
+    }
+}
+
+/// 检索结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub id: u64,
+    pub score: f64,
+}
+
+struct Document {
+    /// 位置数，作为 BM25 的文档长度
+    len: u32,
+    terms: Vec<String>,
+}
+
+/// 倒排索引，可以随时加入和删除内容
+/// ```rust
+/// use rust_helloworld::content::{Post, SearchIndex, Tweet};
+///
+/// let mut index = SearchIndex::new();
+/// index.add(&Post::new(1, "Rust 语言介绍", "Sunface", "Rust 的所有权系统保证了内存安全"));
+/// index.add(&Post::new(2, "Go 语言介绍", "Rob", "Go 有垃圾回收，内存管理很省心"));
+/// index.add(&Tweet::at(3, "ferris", "Memory safety without garbage collection", 0).unwrap());
+///
+/// let ids = |hits: Vec<rust_helloworld::content::Hit>| hits.iter().map(|h| h.id).collect::<Vec<_>>();
+/// assert_eq!(ids(index.search("所有权", 10)), vec![1]);
+/// assert_eq!(ids(index.search("内存", 10)).len(), 2);
+/// assert_eq!(ids(index.search("Garbage", 10)), vec![3]);
+/// // 短语要求按顺序紧挨着
+/// assert_eq!(ids(index.search("\"garbage collection\"", 10)), vec![3]);
+/// assert!(index.search("\"collection garbage\"", 10).is_empty());
+///
+/// assert!(index.remove(1));
+/// assert!(index.search("所有权", 10).is_empty());
+/// assert_eq!(index.len(), 2);
+/// ```
+#[derive(Default)]
+pub struct SearchIndex {
+    /// 词 -> 内容 id -> 出现的位置（升序）
+    postings: HashMap<String, BTreeMap<u64, Vec<u32>>>,
+    docs: HashMap<u64, Document>,
+    total_len: u64,
+}
+
+impl SearchIndex {
+    pub fn new() -> SearchIndex {
+        SearchIndex::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.docs.contains_key(&id)
+    }
+
+    /// 加入内容，同一个 id 已经存在时替换旧的内容
+    pub fn add(&mut self, item: &dyn Searchable) {
+        self.insert(item.search_id(), &item.search_text());
+    }
+
+    /// 直接按 id 索引一段文字，同一个 id 已经存在时替换旧的内容
+    pub fn insert(&mut self, id: u64, text: &str) {
+        self.remove(id);
+        let tokens = tokenize(text, true);
+        let len = tokens.iter().map(|&(_, pos)| pos + 1).max().unwrap_or(0);
+        let mut terms = Vec::new();
+        for (term, pos) in tokens {
+            let positions = self
+                .postings
+                .entry(term.clone())
+                .or_default()
+                .entry(id)
+                .or_default();
+            if positions.is_empty() {
+                terms.push(term);
+            }
+            positions.push(pos);
+        }
+        self.total_len += len as u64;
+        self.docs.insert(id, Document { len, terms });
+    }
+
+    /// 删除内容，内容不存在时返回 false
+    pub fn remove(&mut self, id: u64) -> bool {
+        let doc = match self.docs.remove(&id) {
+            Some(doc) => doc,
+            None => return false,
+        };
+        for term in doc.terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(&id);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+        self.total_len -= doc.len as u64;
+        true
+    }
+
+    /// 检索，按得分从高到低返回最多 `limit` 条，得分相同时 id 小的在前
+    ///
+    /// 包含任意一个词的内容都会出现在结果里，有短语时只返回包含所有短语的内容。
+    /// ```rust
+    /// use rust_helloworld::content::SearchIndex;
+    ///
+    /// let mut index = SearchIndex::new();
+    /// index.insert(1, "rust rust rust");
+    /// index.insert(2, "rust is a language with a long description");
+    /// index.insert(3, "python");
+    /// let hits = index.search("rust", 10);
+    /// assert_eq!(hits.iter().map(|h| h.id).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert!(hits[0].score > hits[1].score);
+    /// assert_eq!(index.search("rust python", 1).len(), 1);
+    /// ```
+    pub fn search(&self, query: &str, limit: usize) -> Vec<Hit> {
+        let query = Query::parse(query);
+        let mut scores: HashMap<u64, f64> = HashMap::new();
+        for term in query.all_terms() {
+            let docs = match self.postings.get(term) {
+                Some(docs) => docs,
+                None => continue,
+            };
+            let idf = self.idf(docs.len());
+            for (&id, positions) in docs {
+                *scores.entry(id).or_insert(0.0) += idf * self.tf_weight(id, positions.len());
+            }
+        }
+
+        let mut hits: Vec<Hit> = scores
+            .into_iter()
+            .filter(|(id, _)| query.phrases.iter().all(|p| self.has_phrase(*id, p)))
+            .map(|(id, score)| Hit { id, score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+        hits.truncate(limit);
+        hits
+    }
+
+    fn idf(&self, df: usize) -> f64 {
+        let n = self.docs.len() as f64;
+        let df = df as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    fn tf_weight(&self, id: u64, tf: usize) -> f64 {
+        let avg = self.total_len as f64 / self.docs.len().max(1) as f64;
+        let len = self.docs[&id].len as f64;
+        let tf = tf as f64;
+        tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg.max(1.0)))
+    }
+
+    fn positions(&self, term: &str, id: u64) -> Option<&Vec<u32>> {
+        self.postings.get(term)?.get(&id)
+    }
+
+    /// 短语的第一个词出现在 p 时，其余的词都要出现在 p 加上它们在短语中的偏移处
+    fn has_phrase(&self, id: u64, phrase: &[(String, u32)]) -> bool {
+        let (first, rest) = match phrase.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        let starts = match self.positions(&first.0, id) {
+            Some(starts) => starts,
+            None => return false,
+        };
+        starts.iter().any(|&start| {
+            rest.iter().all(|(term, offset)| {
+                let at = start + offset - first.1;
+                self.positions(term, id)
+                    .is_some_and(|positions| positions.binary_search(&at).is_ok())
+            })
+        })
+    }
+}
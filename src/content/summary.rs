@@ -0,0 +1,241 @@
+//! 抽取式摘要：把正文切成句子，用 TF-IDF 给句子打分，按长度限制挑出得分最高的句子
+//!
+//! 中文、日文、韩文没有空格分词，按相邻两个字组成的词计算；其他文字按单词计算。
+use std::collections::{HashMap, HashSet};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 截断时追加的省略号
+const ELLIPSIS: &str = "…";
+
+/// 句末标点
+const TERMINATORS: [char; 8] = ['。', '！', '？', '；', '!', '?', ';', '…'];
+/// 可以跟在句末标点后面的右引号和右括号
+const CLOSERS: [char; 8] = ['”', '’', '」', '』', '）', ')', '"', '\''];
+
+/// 摘要的长度限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// 按 Unicode 标量值（`char`）计数
+    Chars(usize),
+    /// 按用户感知的字符（字素簇）计数，组合字符和 emoji 序列算一个
+    Graphemes(usize),
+}
+
+impl Limit {
+    fn max(&self) -> usize {
+        match *self {
+            Limit::Chars(n) | Limit::Graphemes(n) => n,
+        }
+    }
+
+    fn len(&self, text: &str) -> usize {
+        match self {
+            Limit::Chars(_) => text.chars().count(),
+            Limit::Graphemes(_) => text.graphemes(true).count(),
+        }
+    }
+
+    /// 前 `n` 个单位的字节长度，不会落在字符或字素簇的中间
+    fn byte_len(&self, text: &str, n: usize) -> usize {
+        let end = match self {
+            Limit::Chars(_) => text.char_indices().nth(n).map(|(i, _)| i),
+            Limit::Graphemes(_) => text.grapheme_indices(true).nth(n).map(|(i, _)| i),
+        };
+        end.unwrap_or(text.len())
+    }
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}'   // 扩展 A
+        | '\u{4E00}'..='\u{9FFF}'   // 基本汉字
+        | '\u{AC00}'..='\u{D7AF}'   // 韩文音节
+        | '\u{F900}'..='\u{FAFF}'   // 兼容汉字
+    )
+}
+
+/// 切分句子，支持中文和英文的标点，换行也会结束一句话
+/// ```rust
+/// use rust_helloworld::content::sentences;
+///
+/// assert_eq!(
+///     sentences("Rust 棒极了！它很快。“真的吗？”是的\nPi is 3.14. Yes."),
+///     vec!["Rust 棒极了！", "它很快。", "“真的吗？”", "是的", "Pi is 3.14.", "Yes."]
+/// );
+/// ```
+pub fn sentences(text: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, c)| c);
+        // 英文句点后面必须是空白或者结尾，避免切开小数和缩写
+        let ends = TERMINATORS.contains(&c)
+            || c == '\n'
+            || (c == '.' && next.is_none_or(char::is_whitespace));
+        if !ends {
+            continue;
+        }
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, closer)) = chars.peek() {
+            if !(CLOSERS.contains(&closer) || TERMINATORS.contains(&closer)) {
+                break;
+            }
+            end = j + closer.len_utf8();
+            chars.next();
+        }
+        push_sentence(&mut result, &text[start..end]);
+        start = end;
+    }
+    push_sentence(&mut result, &text[start..]);
+    result
+}
+
+fn push_sentence<'a>(sentences: &mut Vec<&'a str>, sentence: &'a str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+}
+
+/// 分词：连续的汉字按相邻两个字组成词，其他文字按单词，统一小写
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+    let flush_cjk = |cjk: &mut Vec<char>, terms: &mut Vec<String>| {
+        if cjk.len() == 1 {
+            terms.push(cjk[0].to_string());
+        }
+        terms.extend(cjk.windows(2).map(|pair| pair.iter().collect::<String>()));
+        cjk.clear();
+    };
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                terms.push(std::mem::take(&mut word));
+            }
+            cjk.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk, &mut terms);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_cjk(&mut cjk, &mut terms);
+            if !word.is_empty() {
+                terms.push(std::mem::take(&mut word));
+            }
+        }
+    }
+    flush_cjk(&mut cjk, &mut terms);
+    if !word.is_empty() {
+        terms.push(word);
+    }
+    terms
+}
+
+/// 每个句子的 TF-IDF 得分，除以词数的平方根，避免长句总是得分最高
+fn scores(sentences: &[&str]) -> Vec<f64> {
+    let terms: Vec<Vec<String>> = sentences.iter().map(|s| terms(s)).collect();
+    let mut df: HashMap<&str, usize> = HashMap::new();
+    for sentence in &terms {
+        let unique: HashSet<&str> = sentence.iter().map(String::as_str).collect();
+        for term in unique {
+            *df.entry(term).or_insert(0) += 1;
+        }
+    }
+    let n = sentences.len() as f64;
+    // 整篇文章里出现得多的词更能代表主题，所以词频按全文统计
+    let mut tf: HashMap<&str, usize> = HashMap::new();
+    for term in terms.iter().flatten() {
+        *tf.entry(term).or_insert(0) += 1;
+    }
+    terms
+        .iter()
+        .map(|sentence| {
+            if sentence.is_empty() {
+                return 0.0;
+            }
+            let total: f64 = sentence
+                .iter()
+                .map(|term| {
+                    let idf = ((n + 1.0) / (df[term.as_str()] as f64 + 1.0)).ln() + 1.0;
+                    tf[term.as_str()] as f64 * idf
+                })
+                .sum();
+            total / (sentence.len() as f64).sqrt()
+        })
+        .collect()
+}
+
+/// 在不超过 `limit` 的前提下截断，被截断时以省略号结尾（省略号也计入长度）
+/// ```rust
+/// use rust_helloworld::content::{truncate, Limit};
+///
+/// assert_eq!(truncate("Rust 棒极了", Limit::Chars(20)), "Rust 棒极了");
+/// assert_eq!(truncate("Rust 棒极了", Limit::Chars(6)), "Rust…");
+/// // 👨‍👩‍👧 由 5 个 char 组成，按字素簇计数时不会被拆开
+/// assert_eq!(truncate("家庭👨‍👩‍👧很好", Limit::Graphemes(4)), "家庭👨‍👩‍👧…");
+/// assert_eq!(truncate("家庭👨‍👩‍👧很好", Limit::Chars(4)), "家庭👨…");
+/// ```
+pub fn truncate(text: &str, limit: Limit) -> String {
+    if limit.len(text) <= limit.max() {
+        return text.to_string();
+    }
+    if limit.max() == 0 {
+        return String::new();
+    }
+    let end = limit.byte_len(text, limit.max() - 1);
+    format!("{}{}", text[..end].trim_end(), ELLIPSIS)
+}
+
+/// 抽取摘要：从得分不低于平均分的句子中，按得分从高到低挑选放得下的句子，
+/// 再按原文顺序拼接。一句都放不下时截断得分最高的句子。
+/// ```rust
+/// use rust_helloworld::content::{extract, Limit};
+///
+/// let text = "Rust 是一门系统编程语言。Rust 的所有权系统保证了内存安全。\
+///             今天天气不错。Rust 编译器会检查借用和所有权。";
+/// let summary = extract(text, Limit::Chars(40));
+/// assert_eq!(summary, "Rust 的所有权系统保证了内存安全。Rust 编译器会检查借用和所有权。");
+/// assert!(!summary.contains("天气"));
+///
+/// assert_eq!(extract(text, Limit::Chars(10)), "Rust 的所有权…");
+/// ```
+pub fn extract(text: &str, limit: Limit) -> String {
+    let sentences = sentences(text);
+    let scores = scores(&sentences);
+    let mut order: Vec<usize> = (0..sentences.len()).collect();
+    // 得分相同时靠前的句子优先
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+    let mean = scores.iter().sum::<f64>() / scores.len().max(1) as f64;
+
+    let mut chosen = Vec::new();
+    let mut used = 0;
+    for &i in order.iter().filter(|&&i| scores[i] >= mean) {
+        let len = limit.len(sentences[i]) + if chosen.is_empty() { 0 } else { 1 };
+        if used + len <= limit.max() {
+            chosen.push(i);
+            used += len;
+        }
+    }
+    if chosen.is_empty() {
+        return match order.first() {
+            Some(&best) => truncate(sentences[best], limit),
+            None => String::new(),
+        };
+    }
+    chosen.sort();
+
+    let mut summary = String::new();
+    for i in chosen {
+        // 英文句子之间加空格，中文句子直接相连
+        if summary.chars().last().is_some_and(|c| c.is_ascii()) {
+            summary.push(' ');
+        }
+        summary.push_str(sentences[i]);
+    }
+    summary
+}
@@ -0,0 +1,10 @@
+//! 报表和成绩册共用的 CSV 小工具
+
+/// 含有逗号、引号或换行的字段需要用引号包起来，引号写两次
+pub(crate) fn field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
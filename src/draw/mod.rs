@@ -0,0 +1,216 @@
+//! 把图形绘制到不同的目标上：字符画、SVG 文档或者像素画布
+//!
+//! [`Draw`] 描述"画什么"，[`RenderTarget`] 描述"画在哪里"，两者之间通过
+//! [`DrawContext`] 传递当前的位置（变换）、颜色和样式。
+//! ```rust
+//! use rust_helloworld::draw::{render, Draw, DrawContext, Label, Style, SvgDocument, TextBuffer};
+//! use rust_helloworld::geometry::{Circle, Point, Rectangle};
+//! use rust_helloworld::kinds::{PrimaryColor, Rgba};
+//!
+//! let scene: Vec<Box<dyn Draw>> = vec![
+//!     Box::new(Rectangle::new(6.0, 3.0)),
+//!     Box::new(Circle::new(Point::new(10.0, 2.0), 1.5).painted(PrimaryColor::Red.into(), Style::Stroke(1.0))),
+//!     Box::new(Label::new(Point::new(0.0, 4.0), "hi")),
+//! ];
+//!
+//! let mut text = TextBuffer::new(14, 5);
+//! render(&scene, &DrawContext::new(), &mut text);
+//! assert_eq!(text.to_string().lines().next(), Some("######   **   "));
+//! assert_eq!(text.to_string().lines().last(), Some("hi            "));
+//!
+//! let mut svg = SvgDocument::new(14, 5);
+//! render(&scene, &DrawContext::new().color(Rgba::BLACK), &mut svg);
+//! assert!(svg.to_string().contains(r#"stroke="rgb(255,0,0)""#));
+//! ```
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::geometry::{Point, Polygon, Shape, Transform};
+use crate::kinds::Rgba;
+
+mod scene;
+mod svg;
+mod text;
+
+pub use self::scene::{Node, NodeKind, Scene};
+pub use self::svg::SvgDocument;
+pub use self::text::TextBuffer;
+
+/// 填充还是描边
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Style {
+    Fill,
+    /// 按给定的线宽描边
+    Stroke(f64),
+}
+
+/// 绘制时的状态，通过 builder 方法派生出新的上下文
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawContext {
+    pub transform: Transform,
+    pub color: Rgba,
+    pub style: Style,
+}
+
+impl Default for DrawContext {
+    fn default() -> DrawContext {
+        DrawContext::new()
+    }
+}
+
+impl DrawContext {
+    /// 原点处、黑色、填充
+    pub fn new() -> DrawContext {
+        DrawContext {
+            transform: Transform::identity(),
+            color: Rgba::BLACK,
+            style: Style::Fill,
+        }
+    }
+
+    /// 当前坐标系的原点在目标上的位置
+    pub fn position(&self) -> Point<f64> {
+        self.transform.apply(Point::origin())
+    }
+
+    /// 在当前坐标系内再做一次变换
+    pub fn transform(&self, transform: &Transform) -> DrawContext {
+        DrawContext {
+            transform: transform.then(&self.transform),
+            ..*self
+        }
+    }
+
+    pub fn translate(&self, dx: f64, dy: f64) -> DrawContext {
+        self.transform(&Transform::translate(dx, dy))
+    }
+
+    pub fn color(&self, color: Rgba) -> DrawContext {
+        DrawContext { color, ..*self }
+    }
+
+    pub fn style(&self, style: Style) -> DrawContext {
+        DrawContext { style, ..*self }
+    }
+}
+
+/// 绘制目标，坐标已经是目标上的坐标
+pub trait RenderTarget {
+    fn fill(&mut self, polygon: &Polygon, color: Rgba);
+    fn stroke(&mut self, polygon: &Polygon, width: f64, color: Rgba);
+    /// 不闭合的折线，缺省按每一段分别描边
+    fn polyline(&mut self, points: &[Point<f64>], width: f64, color: Rgba) {
+        for segment in points.windows(2) {
+            self.stroke(&Polygon::new(segment.to_vec()), width, color);
+        }
+    }
+    /// 从 `position` 开始向右写一行文字
+    fn text(&mut self, position: Point<f64>, text: &str, color: Rgba);
+}
+
+/// 可以被绘制的东西
+pub trait Draw {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget);
+
+    /// 用指定的颜色和样式绘制，不受外层上下文的颜色和样式影响
+    fn painted(self, color: Rgba, style: Style) -> Painted<Self>
+    where
+        Self: Sized,
+    {
+        Painted {
+            item: self,
+            color,
+            style,
+        }
+    }
+}
+
+/// 所有图形都按上下文的样式画出轮廓或者填充
+impl<S: Shape + ?Sized> Draw for S {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        let polygon = self.to_polygon().transform(&ctx.transform);
+        match ctx.style {
+            Style::Fill => target.fill(&polygon, ctx.color),
+            Style::Stroke(width) => target.stroke(&polygon, width, ctx.color),
+        }
+    }
+}
+
+/// 由 [`Draw::painted`] 生成
+#[derive(Debug, Clone, PartialEq)]
+pub struct Painted<D> {
+    pub item: D,
+    pub color: Rgba,
+    pub style: Style,
+}
+
+impl<D: Draw> Draw for Painted<D> {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        self.item
+            .draw(&ctx.color(self.color).style(self.style), target);
+    }
+}
+
+/// 一行文字，`position` 是第一个字符左上角的位置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub position: Point<f64>,
+    pub text: String,
+}
+
+impl Label {
+    pub fn new(position: Point<f64>, text: &str) -> Label {
+        Label {
+            position,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl Draw for Label {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        target.text(ctx.transform.apply(self.position), &self.text, ctx.color);
+    }
+}
+
+// 数值画成一个写着类型和值的标签
+macro_rules! impl_draw_number {
+    ($($t:ty),*) => {
+        $(
+            impl Draw for $t {
+                fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+                    draw_value(stringify!($t), self, ctx, target);
+                }
+            }
+        )*
+    };
+}
+
+impl_draw_number!(u8, u32, i32, f64);
+
+fn draw_value(name: &str, value: &dyn Display, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+    target.text(ctx.position(), &format!("{}: {}", name, value), ctx.color);
+}
+
+/// 按顺序绘制一组对象，后面的盖在前面的上面
+pub fn render(scene: &[Box<dyn Draw>], ctx: &DrawContext, target: &mut dyn RenderTarget) {
+    for item in scene {
+        item.draw(ctx, target);
+    }
+}
+
+/// 画布没有字体，文字会被忽略
+impl RenderTarget for Canvas {
+    fn fill(&mut self, polygon: &Polygon, color: Rgba) {
+        Canvas::fill(self, polygon, color);
+    }
+
+    fn stroke(&mut self, polygon: &Polygon, width: f64, color: Rgba) {
+        Canvas::stroke(self, polygon, width, color);
+    }
+
+    fn text(&mut self, _position: Point<f64>, _text: &str, _color: Rgba) {}
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Draw, DrawContext, Label, RenderTarget, Style};
+use crate::geometry::{
+    segment_distance, Circle, Point, Polygon, Rectangle, Shape, Transform, Triangle,
+};
+use crate::kinds::Rgba;
+
+/// 节点的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeKind {
+    Rectangle(Rectangle<f64>),
+    Circle(Circle),
+    Triangle(Triangle),
+    Polygon(Polygon),
+    Label(Label),
+    Group { children: Vec<Node> },
+}
+
+impl NodeKind {
+    fn shape(&self) -> Option<&dyn Shape> {
+        match self {
+            NodeKind::Rectangle(rect) => Some(rect),
+            NodeKind::Circle(circle) => Some(circle),
+            NodeKind::Triangle(triangle) => Some(triangle),
+            NodeKind::Polygon(polygon) => Some(polygon),
+            NodeKind::Label(_) | NodeKind::Group { .. } => None,
+        }
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn visible_default() -> bool {
+    true
+}
+
+/// 场景中的一个节点
+///
+/// 变换相对于父节点；颜色和样式为 None 时沿用父节点的设置。
+/// 同一组内 `z` 大的画在上面，`z` 相同时后加入的在上面。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub z: i32,
+    #[serde(default = "visible_default", skip_serializing_if = "is_true")]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Rgba>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<Style>,
+    #[serde(flatten)]
+    pub kind: NodeKind,
+}
+
+impl Node {
+    pub fn new(kind: NodeKind) -> Node {
+        Node {
+            name: String::new(),
+            transform: Transform::identity(),
+            z: 0,
+            visible: true,
+            color: None,
+            style: None,
+            kind,
+        }
+    }
+
+    pub fn group(children: Vec<Node>) -> Node {
+        Node::new(NodeKind::Group { children })
+    }
+
+    pub fn named(mut self, name: &str) -> Node {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Node {
+        self.transform = transform;
+        self
+    }
+
+    pub fn with_z(mut self, z: i32) -> Node {
+        self.z = z;
+        self
+    }
+
+    pub fn with_color(mut self, color: Rgba) -> Node {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Node {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn hidden(mut self) -> Node {
+        self.visible = false;
+        self
+    }
+
+    pub fn children(&self) -> &[Node] {
+        match &self.kind {
+            NodeKind::Group { children } => children,
+            _ => &[],
+        }
+    }
+
+    fn context(&self, parent: &DrawContext) -> DrawContext {
+        let mut ctx = parent.transform(&self.transform);
+        if let Some(color) = self.color {
+            ctx = ctx.color(color);
+        }
+        if let Some(style) = self.style {
+            ctx = ctx.style(style);
+        }
+        ctx
+    }
+
+    fn find(&self, name: &str) -> Option<&Node> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children().iter().find_map(|child| child.find(name))
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut Node> {
+        if self.name == name {
+            return Some(self);
+        }
+        match &mut self.kind {
+            NodeKind::Group { children } => {
+                children.iter_mut().find_map(|child| child.find_mut(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// `point` 落在哪个最上层的可见图形上，文字不参与命中测试
+    fn hit_test(&self, point: &Point<f64>, parent: &DrawContext) -> Option<&Node> {
+        if !self.visible {
+            return None;
+        }
+        let ctx = self.context(parent);
+        if let NodeKind::Group { children } = &self.kind {
+            return by_z(children)
+                .into_iter()
+                .rev()
+                .find_map(|child| child.hit_test(point, &ctx));
+        }
+        let polygon = self.kind.shape()?.to_polygon().transform(&ctx.transform);
+        let hit = match ctx.style {
+            Style::Fill => polygon.contains(point),
+            Style::Stroke(width) => polygon
+                .edges()
+                .any(|(a, b)| segment_distance(*point, a, b) <= width / 2.0),
+        };
+        if hit {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+/// 按绘制顺序排列，排序是稳定的
+fn by_z(nodes: &[Node]) -> Vec<&Node> {
+    let mut nodes: Vec<&Node> = nodes.iter().collect();
+    nodes.sort_by_key(|node| node.z);
+    nodes
+}
+
+impl Draw for Node {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        if !self.visible {
+            return;
+        }
+        let ctx = self.context(ctx);
+        match &self.kind {
+            NodeKind::Rectangle(rect) => rect.draw(&ctx, target),
+            NodeKind::Circle(circle) => circle.draw(&ctx, target),
+            NodeKind::Triangle(triangle) => triangle.draw(&ctx, target),
+            NodeKind::Polygon(polygon) => polygon.draw(&ctx, target),
+            NodeKind::Label(label) => label.draw(&ctx, target),
+            NodeKind::Group { children } => {
+                for child in by_z(children) {
+                    child.draw(&ctx, target);
+                }
+            }
+        }
+    }
+}
+
+/// 场景图：顶层节点按 z 排序绘制，组可以嵌套，并且可以保存为 JSON 再读回来
+/// ```rust
+/// use rust_helloworld::draw::{Node, NodeKind, Scene, Style, TextBuffer, Draw, DrawContext};
+/// use rust_helloworld::geometry::{Circle, Point, Rectangle, Transform};
+/// use rust_helloworld::kinds::Rgba;
+///
+/// let mut scene = Scene::new();
+/// scene.add(Node::new(NodeKind::Rectangle(Rectangle::new(10.0, 6.0))).named("back"));
+/// scene.add(
+///     Node::group(vec![
+///         Node::new(NodeKind::Circle(Circle::new(Point::new(0.0, 0.0), 2.0))).named("dot"),
+///         Node::new(NodeKind::Rectangle(Rectangle::new(2.0, 2.0))).named("hidden").hidden(),
+///     ])
+///     .with_transform(Transform::translate(5.0, 3.0))
+///     .with_z(1)
+///     .with_color(Rgba::WHITE),
+/// );
+///
+/// assert_eq!(scene.hit_test(&Point::new(5.0, 3.0)).map(|n| n.name.as_str()), Some("dot"));
+/// assert_eq!(scene.hit_test(&Point::new(1.0, 1.0)).map(|n| n.name.as_str()), Some("back"));
+/// assert_eq!(scene.hit_test(&Point::new(20.0, 1.0)), None);
+/// // 隐藏的节点不会被命中
+/// assert_eq!(scene.hit_test(&Point::new(6.8, 4.8)).map(|n| n.name.as_str()), Some("back"));
+///
+/// // 把背景改成描边后，它的内部不再被命中
+/// scene.find_mut("back").unwrap().style = Some(Style::Stroke(1.0));
+/// assert_eq!(scene.hit_test(&Point::new(1.0, 1.0)), None);
+///
+/// let json = scene.to_json();
+/// let reloaded = Scene::from_json(&json).unwrap();
+/// assert_eq!(reloaded, scene);
+///
+/// let mut text = TextBuffer::new(10, 6);
+/// reloaded.draw(&DrawContext::new(), &mut text);
+/// assert_eq!(text.get(5, 3), Some('#'));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Scene {
+    pub nodes: Vec<Node>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene { nodes: Vec::new() }
+    }
+
+    pub fn add(&mut self, node: Node) {
+        self.nodes.push(node);
+    }
+
+    /// 按名字查找节点，包括组内的节点
+    pub fn find(&self, name: &str) -> Option<&Node> {
+        self.nodes.iter().find_map(|node| node.find(name))
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut Node> {
+        self.nodes.iter_mut().find_map(|node| node.find_mut(name))
+    }
+
+    /// `point` 处最上层的可见图形，隐藏的组连同其中的节点都不会被命中
+    pub fn hit_test(&self, point: &Point<f64>) -> Option<&Node> {
+        let ctx = DrawContext::new();
+        by_z(&self.nodes)
+            .into_iter()
+            .rev()
+            .find_map(|node| node.hit_test(point, &ctx))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scene is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Scene, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl Draw for Scene {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        for node in by_z(&self.nodes) {
+            node.draw(ctx, target);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::{Display, Formatter};
+
+use super::RenderTarget;
+use crate::geometry::{Point, Polygon};
+use crate::kinds::Rgba;
+
+/// SVG 文档，每个图形输出为一个 `<polygon>`，折线输出为 `<polyline>`，文字输出为 `<text>`
+pub struct SvgDocument {
+    width: u32,
+    height: u32,
+    elements: Vec<String>,
+}
+
+impl SvgDocument {
+    pub fn new(width: u32, height: u32) -> SvgDocument {
+        SvgDocument {
+            width,
+            height,
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn elements(&self) -> &[String] {
+        &self.elements
+    }
+}
+
+fn points(polygon: &Polygon) -> String {
+    let points: Vec<String> = polygon
+        .points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect();
+    points.join(" ")
+}
+
+/// 颜色属性，不透明时省略 opacity
+fn paint(attr: &str, color: Rgba) -> String {
+    let mut s = format!(r#"{}="rgb({},{},{})""#, attr, color.r, color.g, color.b);
+    if color.a < 255 {
+        s.push_str(&format!(
+            r#" {}-opacity="{:.3}""#,
+            attr,
+            color.a as f64 / 255.0
+        ));
+    }
+    s
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl RenderTarget for SvgDocument {
+    fn fill(&mut self, polygon: &Polygon, color: Rgba) {
+        self.elements.push(format!(
+            r#"<polygon points="{}" {}/>"#,
+            points(polygon),
+            paint("fill", color)
+        ));
+    }
+
+    fn stroke(&mut self, polygon: &Polygon, width: f64, color: Rgba) {
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="none" {} stroke-width="{}"/>"#,
+            points(polygon),
+            paint("stroke", color),
+            width
+        ));
+    }
+
+    fn polyline(&mut self, points: &[Point<f64>], width: f64, color: Rgba) {
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" {} stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+            self::points(&Polygon::new(points.to_vec())),
+            paint("stroke", color),
+            width
+        ));
+    }
+
+    fn text(&mut self, position: Point<f64>, text: &str, color: Rgba) {
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" dominant-baseline="hanging" {}>{}</text>"#,
+            position.x,
+            position.y,
+            paint("fill", color),
+            escape(text)
+        ));
+    }
+}
+
+impl Display for SvgDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.width,
+            h = self.height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+        write!(f, "</svg>")
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{Display, Formatter};
+
+use super::RenderTarget;
+use crate::geometry::{segment_distance, Point, Polygon, Shape};
+use crate::kinds::Rgba;
+
+/// 字符画，每个字符是一个单位大小的格子，以格子中心是否落在图形内决定是否画上
+///
+/// 填充画成 `#`，描边画成 `*`，颜色只用来跳过完全透明的图形。
+pub struct TextBuffer {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+}
+
+impl TextBuffer {
+    pub fn new(width: usize, height: usize) -> TextBuffer {
+        TextBuffer {
+            width,
+            height,
+            cells: vec![' '; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.cells[y * self.width + x])
+    }
+
+    fn set(&mut self, x: i64, y: i64, c: char) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.cells[y as usize * self.width + x as usize] = c;
+        }
+    }
+
+    fn paint<F: Fn(&Point<f64>) -> bool>(
+        &mut self,
+        polygon: &Polygon,
+        margin: f64,
+        c: char,
+        inside: F,
+    ) {
+        let bounds = polygon.bounding_box();
+        let x0 = (bounds.min_x() - margin).floor().max(0.0) as usize;
+        let y0 = (bounds.min_y() - margin).floor().max(0.0) as usize;
+        let x1 = ((bounds.max_x() + margin).ceil().max(0.0) as usize).min(self.width);
+        let y1 = ((bounds.max_y() + margin).ceil().max(0.0) as usize).min(self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if inside(&Point::new(x as f64 + 0.5, y as f64 + 0.5)) {
+                    self.cells[y * self.width + x] = c;
+                }
+            }
+        }
+    }
+}
+
+impl RenderTarget for TextBuffer {
+    fn fill(&mut self, polygon: &Polygon, color: Rgba) {
+        if color.a > 0 {
+            self.paint(polygon, 0.0, '#', |p| polygon.contains(p));
+        }
+    }
+
+    fn stroke(&mut self, polygon: &Polygon, width: f64, color: Rgba) {
+        if color.a == 0 {
+            return;
+        }
+        // 线宽不足一格时仍然画出一格宽的线
+        let half = (width / 2.0).max(0.5);
+        self.paint(polygon, half, '*', |p| {
+            polygon
+                .edges()
+                .any(|(a, b)| segment_distance(*p, a, b) <= half)
+        });
+    }
+
+    fn text(&mut self, position: Point<f64>, text: &str, color: Rgba) {
+        if color.a == 0 {
+            return;
+        }
+        let (x, y) = (position.x.floor() as i64, position.y.floor() as i64);
+        for (i, c) in text.chars().enumerate() {
+            self.set(x + i as i64, y, c);
+        }
+    }
+}
+
+impl Display for TextBuffer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
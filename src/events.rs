@@ -0,0 +1,194 @@
+//! 订单生命周期的事件溯源
+//!
+//! 订单的每一次变化都记录成一条不可变的 [`OrderEvent`]，追加写入存储中的事件日志。
+//! 依次应用事件即可投影出订单的当前状态，也可以逐条回放，用来核对有争议的订单。
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::front_of_house::serving::{Order, OrderItem, OrderStatus, Payment};
+use crate::storage::{Storage, StorageError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrderEventKind {
+    Taken {
+        table: u32,
+        covers: u32,
+        items: Vec<OrderItem>,
+    },
+    Modified {
+        items: Vec<OrderItem>,
+    },
+    Voided {
+        reason: String,
+    },
+    Cooked,
+    Served,
+    Paid {
+        payment_id: u64,
+        amount: u64,
+        tip: u64,
+    },
+    /// 通过 `fix_incorrect_order` 重做
+    Remade,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderEvent {
+    pub order_id: u64,
+    /// 发生时间（unix 秒）
+    pub at: u64,
+    /// 操作人
+    pub actor: String,
+    pub kind: OrderEventKind,
+}
+
+impl OrderEventKind {
+    /// 事件类型的名字，与序列化时的 `type` 一致
+    pub fn name(&self) -> &'static str {
+        match self {
+            OrderEventKind::Taken { .. } => "taken",
+            OrderEventKind::Modified { .. } => "modified",
+            OrderEventKind::Voided { .. } => "voided",
+            OrderEventKind::Cooked => "cooked",
+            OrderEventKind::Served => "served",
+            OrderEventKind::Paid { .. } => "paid",
+            OrderEventKind::Remade => "remade",
+        }
+    }
+}
+
+impl OrderEvent {
+    pub fn new(order_id: u64, at: u64, actor: &str, kind: OrderEventKind) -> OrderEvent {
+        OrderEvent {
+            order_id,
+            at,
+            actor: actor.to_string(),
+            kind,
+        }
+    }
+
+    /// `Paid` 事件对应的付款，付款记录都由这个事件得出
+    pub fn payment(&self) -> Option<Payment> {
+        match self.kind {
+            OrderEventKind::Paid {
+                payment_id,
+                amount,
+                tip,
+            } => Some(Payment {
+                id: payment_id,
+                order_id: self.order_id,
+                amount,
+                tip,
+                paid_at: self.at,
+            }),
+            _ => None,
+        }
+    }
+
+    /// 把事件应用到订单上，得到新的订单状态
+    ///
+    /// 除了 `Taken` 之外，其它事件都需要订单已经存在，否则返回 None。
+    pub fn apply(&self, order: Option<Order>) -> Option<Order> {
+        let mut order = match (&self.kind, order) {
+            (
+                OrderEventKind::Taken {
+                    table,
+                    covers,
+                    items,
+                },
+                _,
+            ) => {
+                return Some(Order {
+                    id: self.order_id,
+                    table: *table,
+                    covers: *covers,
+                    items: items.clone(),
+                    status: OrderStatus::Open,
+                    opened_at: self.at,
+                })
+            }
+            (_, None) => return None,
+            (_, Some(order)) => order,
+        };
+        match &self.kind {
+            OrderEventKind::Taken { .. } | OrderEventKind::Remade => {}
+            OrderEventKind::Modified { items } => order.items = items.clone(),
+            OrderEventKind::Voided { .. } => order.status = OrderStatus::Voided,
+            OrderEventKind::Cooked => order.status = OrderStatus::Cooked,
+            OrderEventKind::Served => order.status = OrderStatus::Served,
+            OrderEventKind::Paid { .. } => order.status = OrderStatus::Paid,
+        }
+        Some(order)
+    }
+}
+
+/// 检查订单当前状态是否允许发生该事件，见 [`OrderStatus::allows`]
+pub(crate) fn check(order: &Order, kind: &OrderEventKind) -> Result<(), StorageError> {
+    if order.status.allows(kind) {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidTransition {
+            order_id: order.id,
+            status: order.status,
+            event: kind.name(),
+        })
+    }
+}
+
+/// 把事件写入日志，并保存投影后的订单，订单不存在时不写入并返回 None，
+/// 订单状态不允许该事件时不写入并返回错误
+pub(crate) fn commit(
+    store: &mut dyn Storage,
+    event: OrderEvent,
+) -> Result<Option<Order>, StorageError> {
+    let current = store.order(event.order_id)?;
+    if let Some(order) = &current {
+        check(order, &event.kind)?;
+    }
+    let order = match event.apply(current) {
+        Some(order) => order,
+        None => return Ok(None),
+    };
+    store.append_event(event)?;
+    store.put_order(order.clone())?;
+    Ok(Some(order))
+}
+
+/// 依次应用所有事件，投影出每个订单的当前状态
+/// ```rust
+/// use rust_helloworld::events::{project, OrderEvent, OrderEventKind};
+/// use rust_helloworld::front_of_house::serving::{OrderItem, OrderStatus};
+///
+/// let items = vec![OrderItem::new("米饭", "主食", 200, 1)];
+/// let events = vec![
+///     OrderEvent::new(1, 100, "waiter", OrderEventKind::Taken { table: 3, covers: 2, items }),
+///     OrderEvent::new(1, 200, "chef", OrderEventKind::Cooked),
+///     OrderEvent::new(1, 300, "manager", OrderEventKind::Voided { reason: "客人离开".to_string() }),
+/// ];
+/// let orders = project(&events);
+/// assert_eq!(orders[&1].status, OrderStatus::Voided);
+/// ```
+pub fn project(events: &[OrderEvent]) -> BTreeMap<u64, Order> {
+    let mut orders = BTreeMap::new();
+    for event in events {
+        if let Some(order) = event.apply(orders.remove(&event.order_id)) {
+            orders.insert(order.id, order);
+        }
+    }
+    orders
+}
+
+/// 回放某个订单的全部事件，返回每条事件以及应用之后的订单状态
+pub fn history(events: &[OrderEvent], order_id: u64) -> Vec<(OrderEvent, Order)> {
+    let mut steps = Vec::new();
+    let mut order = None;
+    for event in events.iter().filter(|event| event.order_id == order_id) {
+        order = event.apply(order);
+        if let Some(order) = &order {
+            steps.push((event.clone(), order.clone()));
+        }
+    }
+    steps
+}
@@ -1,8 +1,107 @@
-pub fn add_to_waitlist() {}
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::storage::{Storage, StorageError};
+use crate::utils::now;
+
+/// 候位的一桌客人
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    pub id: u64,
+    pub name: String,
+    /// 就餐人数
+    pub size: u32,
+    /// 加入候位的时间（unix 秒）
+    pub joined_at: u64,
+}
+
+/// 预订
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reservation {
+    pub id: u64,
+    pub name: String,
+    pub size: u32,
+    /// 预订的到店时间（unix 秒）
+    pub time: u64,
+}
+
+/// 入座结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Seating {
+    pub party: Party,
+    pub table: u32,
+}
+
+impl Seating {
+    pub fn greeting(&self) -> String {
+        tr("seating.greeting", &[])
+    }
+}
+
+/// 把客人加入候位名单
+/// ```rust
+/// use rust_helloworld::front_of_house::hosting::{add_to_waitlist, seat_at_table};
+/// use rust_helloworld::storage::{MemoryStorage, Storage};
+///
+/// let mut store = MemoryStorage::new();
+/// let party = add_to_waitlist(&mut store, "张三", 4).unwrap();
+/// assert_eq!(store.waitlist().unwrap().len(), 1);
+///
+/// let seating = seat_at_table(&mut store, party.id, 7).unwrap().unwrap();
+/// assert_eq!(seating.table, 7);
+/// assert!(store.waitlist().unwrap().is_empty());
+/// ```
+pub fn add_to_waitlist(
+    store: &mut dyn Storage,
+    name: &str,
+    size: u32,
+) -> Result<Party, StorageError> {
+    let party = Party {
+        id: store.next_id()?,
+        name: name.to_string(),
+        size,
+        joined_at: now(),
+    };
+    store.push_party(party.clone())?;
+    Ok(party)
+}
+
+/// 预订座位
+pub fn reserve(
+    store: &mut dyn Storage,
+    name: &str,
+    size: u32,
+    time: u64,
+) -> Result<Reservation, StorageError> {
+    let reservation = Reservation {
+        id: store.next_id()?,
+        name: name.to_string(),
+        size,
+        time,
+    };
+    store.put_reservation(reservation.clone())?;
+    Ok(reservation)
+}
+
+/// 取消预订，预订不存在时返回 None
+pub fn cancel_reservation(
+    store: &mut dyn Storage,
+    id: u64,
+) -> Result<Option<Reservation>, StorageError> {
+    store.remove_reservation(id)
+}
 
 /**
 这是一个测试
+
+带候位的客人入座，客人不在候位名单中时返回 None
  */
-pub fn seat_at_table() -> String {
-    String::from("sit down please")
+pub fn seat_at_table(
+    store: &mut dyn Storage,
+    party_id: u64,
+    table: u32,
+) -> Result<Option<Seating>, StorageError> {
+    Ok(store
+        .remove_party(party_id)?
+        .map(|party| Seating { party, table }))
 }
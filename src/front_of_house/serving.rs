@@ -1,8 +1,256 @@
-pub fn take_order() {}
+use serde::{Deserialize, Serialize};
 
-pub fn serve_order() {}
+use crate::events::{self, OrderEvent, OrderEventKind};
+use crate::i18n::tr;
+use crate::storage::{Storage, StorageError};
+use crate::utils::now;
 
-pub fn take_payment() {}
+/// 点单中的一道菜，价格单位为分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderItem {
+    pub name: String,
+    pub category: String,
+    pub price: u64,
+    pub quantity: u32,
+    /// 招待（免单）的菜不计入应收金额
+    #[serde(default)]
+    pub comped: bool,
+}
+
+impl OrderItem {
+    pub fn new(name: &str, category: &str, price: u64, quantity: u32) -> OrderItem {
+        OrderItem {
+            name: name.to_string(),
+            category: category.to_string(),
+            price,
+            quantity,
+            comped: false,
+        }
+    }
+
+    /// 标记为招待
+    pub fn comp(mut self) -> OrderItem {
+        self.comped = true;
+        self
+    }
+
+    /// 按菜单价格计算的金额，包含招待的部分
+    pub fn list_total(&self) -> u64 {
+        self.price * self.quantity as u64
+    }
+
+    /// 应收金额
+    pub fn total(&self) -> u64 {
+        if self.comped {
+            0
+        } else {
+            self.list_total()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Open,
+    Cooked,
+    Served,
+    Paid,
+    Voided,
+}
+
+impl OrderStatus {
+    /// 已付款或已作废的订单不能再发生任何变化
+    pub fn is_closed(self) -> bool {
+        matches!(self, OrderStatus::Paid | OrderStatus::Voided)
+    }
+
+    /// 当前状态下是否允许发生该事件
+    /// ```rust
+    /// use rust_helloworld::events::OrderEventKind;
+    /// use rust_helloworld::front_of_house::serving::OrderStatus;
+    ///
+    /// let paid = OrderEventKind::Paid { payment_id: 2, amount: 100, tip: 0 };
+    /// assert!(OrderStatus::Served.allows(&paid));
+    /// assert!(!OrderStatus::Paid.allows(&paid));
+    /// assert!(!OrderStatus::Voided.allows(&OrderEventKind::Cooked));
+    /// ```
+    pub fn allows(self, kind: &OrderEventKind) -> bool {
+        match kind {
+            // 订单只会被创建一次
+            OrderEventKind::Taken { .. } => false,
+            OrderEventKind::Modified { .. }
+            | OrderEventKind::Voided { .. }
+            | OrderEventKind::Cooked
+            | OrderEventKind::Served
+            | OrderEventKind::Paid { .. }
+            | OrderEventKind::Remade => !self.is_closed(),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Cooked => "cooked",
+            OrderStatus::Served => "served",
+            OrderStatus::Paid => "paid",
+            OrderStatus::Voided => "voided",
+        }
+    }
+
+    /// 当前语言下的名字
+    pub fn label(self) -> String {
+        tr(&format!("order.status.{}", self.as_str()), &[])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: u64,
+    pub table: u32,
+    /// 就餐人数
+    pub covers: u32,
+    pub items: Vec<OrderItem>,
+    pub status: OrderStatus,
+    /// 下单时间（unix 秒）
+    pub opened_at: u64,
+}
+
+impl Order {
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|item| item.total()).sum()
+    }
+}
+
+/// 一笔付款，金额单位为分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: u64,
+    pub order_id: u64,
+    pub amount: u64,
+    pub tip: u64,
+    pub paid_at: u64,
+}
+
+/// 点单
+/// ```rust
+/// use rust_helloworld::front_of_house::serving::{take_order, take_payment, OrderItem, OrderStatus};
+/// use rust_helloworld::storage::{MemoryStorage, Storage};
+///
+/// let mut store = MemoryStorage::new();
+/// let items = vec![OrderItem::new("宫保鸡丁", "热菜", 3800, 2)];
+/// let order = take_order(&mut store, "waiter", 7, 4, items).unwrap();
+/// assert_eq!(order.total(), 7600);
+///
+/// take_payment(&mut store, "cashier", order.id, 1000).unwrap();
+/// assert_eq!(store.order(order.id).unwrap().unwrap().status, OrderStatus::Paid);
+/// assert_eq!(store.payments().unwrap()[0].amount, 7600);
+/// assert_eq!(store.events().unwrap().len(), 2);
+/// ```
+pub fn take_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    table: u32,
+    covers: u32,
+    items: Vec<OrderItem>,
+) -> Result<Order, StorageError> {
+    let id = store.next_id()?;
+    let kind = OrderEventKind::Taken {
+        table,
+        covers,
+        items,
+    };
+    let order = events::commit(store, OrderEvent::new(id, now(), actor, kind))?;
+    // Taken 事件总会生成订单
+    Ok(order.expect("taken event creates the order"))
+}
+
+/// 修改订单中的菜品，订单不存在时返回 None
+pub fn modify_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+    items: Vec<OrderItem>,
+) -> Result<Option<Order>, StorageError> {
+    let kind = OrderEventKind::Modified { items };
+    events::commit(store, OrderEvent::new(order_id, now(), actor, kind))
+}
+
+/// 作废订单，订单不存在时返回 None，已付款或已作废时返回错误
+/// ```rust
+/// use rust_helloworld::back_of_house::cook_order;
+/// use rust_helloworld::front_of_house::serving::{serve_order, take_order, take_payment, void_order};
+/// use rust_helloworld::storage::{MemoryStorage, Storage, StorageError};
+///
+/// let mut store = MemoryStorage::new();
+/// let paid = take_order(&mut store, "waiter", 1, 2, vec![]).unwrap();
+/// take_payment(&mut store, "cashier", paid.id, 0).unwrap();
+/// let err = void_order(&mut store, "manager", paid.id, "").unwrap_err();
+/// assert!(matches!(err, StorageError::InvalidTransition { .. }));
+///
+/// let voided = take_order(&mut store, "waiter", 2, 2, vec![]).unwrap();
+/// void_order(&mut store, "manager", voided.id, "客人离开").unwrap();
+/// assert!(cook_order(&mut store, "chef", voided.id).is_err());
+/// assert!(serve_order(&mut store, "waiter", voided.id).is_err());
+/// assert!(take_payment(&mut store, "cashier", voided.id, 0).is_err());
+/// // 被拒绝的操作不会写入事件
+/// assert_eq!(store.events().unwrap().len(), 4);
+/// ```
+pub fn void_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+    reason: &str,
+) -> Result<Option<Order>, StorageError> {
+    let kind = OrderEventKind::Voided {
+        reason: reason.to_string(),
+    };
+    events::commit(store, OrderEvent::new(order_id, now(), actor, kind))
+}
+
+/// 上菜，订单不存在时返回 None
+pub fn serve_order(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+) -> Result<Option<Order>, StorageError> {
+    let event = OrderEvent::new(order_id, now(), actor, OrderEventKind::Served);
+    events::commit(store, event)
+}
+
+/// 按订单总额收款，订单不存在时返回 None，已付款或已作废时返回错误且不会生成付款
+/// ```rust
+/// use rust_helloworld::front_of_house::serving::{take_order, take_payment};
+/// use rust_helloworld::storage::{MemoryStorage, Storage, StorageError};
+///
+/// let mut store = MemoryStorage::new();
+/// let order = take_order(&mut store, "waiter", 1, 2, vec![]).unwrap();
+/// take_payment(&mut store, "cashier", order.id, 0).unwrap();
+/// let err = take_payment(&mut store, "cashier", order.id, 0).unwrap_err();
+/// assert!(matches!(err, StorageError::InvalidTransition { .. }));
+/// assert_eq!(store.payments().unwrap().len(), 1);
+/// ```
+pub fn take_payment(
+    store: &mut dyn Storage,
+    actor: &str,
+    order_id: u64,
+    tip: u64,
+) -> Result<Option<Payment>, StorageError> {
+    let order = match store.order(order_id)? {
+        Some(order) => order,
+        None => return Ok(None),
+    };
+    let kind = OrderEventKind::Paid {
+        payment_id: store.next_id()?,
+        amount: order.total(),
+        tip,
+    };
+    let event = OrderEvent::new(order_id, now(), actor, kind);
+    let payment = event.payment();
+    events::commit(store, event)?;
+    Ok(payment)
+}
 
 // 我猜你不希望顾客听到你在抱怨他们，因此让这个函数私有化吧
+#[allow(dead_code)]
 fn complain() {}
@@ -0,0 +1,70 @@
+use std::fmt::Debug;
+
+use super::Scalar;
+
+/// 面积的计算方式：宽乘高可能溢出坐标类型，因此提供检查、饱和以及加宽三种乘法
+///
+/// `Wide` 是加宽后的面积类型，能放下任意两个坐标值的乘积，例如 `u32` 的面积用 `u64` 表示。
+pub trait AreaScalar: Scalar {
+    type Wide: Copy + Debug + PartialOrd;
+
+    fn widening_mul(self, rhs: Self) -> Self::Wide;
+    /// 溢出时返回 None
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// 溢出时取类型的最大值或者最小值
+    fn saturating_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_area_int {
+    ($($t:ty => $wide:ty),* $(,)?) => {
+        $(
+            impl AreaScalar for $t {
+                type Wide = $wide;
+
+                fn widening_mul(self, rhs: Self) -> $wide {
+                    self as $wide * rhs as $wide
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn saturating_mul(self, rhs: Self) -> Self {
+                    <$t>::saturating_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_area_int! {
+    i32 => i64,
+    i64 => i128,
+    u32 => u64,
+    u64 => u128,
+}
+
+macro_rules! impl_area_float {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl AreaScalar for $t {
+                type Wide = f64;
+
+                fn widening_mul(self, rhs: Self) -> f64 {
+                    self as f64 * rhs as f64
+                }
+
+                // 浮点数溢出会得到无穷大
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    Some(self * rhs).filter(|area| area.is_finite())
+                }
+
+                fn saturating_mul(self, rhs: Self) -> Self {
+                    (self * rhs).clamp(<$t>::MIN, <$t>::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_area_float!(f32, f64);
@@ -0,0 +1,87 @@
+//! 几何图形：点、矩形、圆、三角形、多边形，仿射变换，以及矩形的空间索引
+//!
+//! 坐标类型通过 [`Scalar`] 泛化，整数和浮点数都可以使用；[`Point`] 同时支持向量运算。
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+mod area;
+mod point;
+mod rect;
+mod shape;
+mod spatial;
+mod transform;
+
+pub use self::area::AreaScalar;
+pub use self::point::{Point, TryFromPointError};
+pub use self::rect::Rectangle;
+pub use self::shape::{Circle, Polygon, Shape, Transformed, Triangle};
+pub use self::spatial::{ItemId, QuadTree};
+pub use self::transform::Transform;
+
+/// 可以作为坐标的数值类型
+pub trait Scalar:
+    Copy + Debug + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_f64(self) -> f64;
+
+    /// 整数溢出时停在最大值或最小值，浮点数就是普通的加法
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty => $zero:expr, $one:expr, $add:ident);* $(;)?) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::$add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar! {
+    i32 => 0, 1, saturating_add;
+    i64 => 0, 1, saturating_add;
+    u32 => 0, 1, saturating_add;
+    u64 => 0, 1, saturating_add;
+    f32 => 0.0, 1.0, add;
+    f64 => 0.0, 1.0, add;
+}
+
+pub(crate) fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+pub(crate) fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// 点到线段的距离
+pub(crate) fn segment_distance(p: Point<f64>, a: Point<f64>, b: Point<f64>) -> f64 {
+    let ab = b - a;
+    let len2 = ab.dot(&ab);
+    if len2 == 0.0 {
+        return p.distance(&a);
+    }
+    let t = ((p - a).dot(&ab) / len2).clamp(0.0, 1.0);
+    p.distance(&(a + ab * t))
+}
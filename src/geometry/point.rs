@@ -0,0 +1,163 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use super::Scalar;
+use crate::i18n::tr;
+
+/// 二维平面上的点，也可以当作从原点出发的向量使用
+/// ```rust
+/// use rust_helloworld::geometry::Point;
+///
+/// let a = Point::new(3, 4);
+/// let b = Point::new(1, 2);
+/// assert_eq!(a + b, Point::new(4, 6));
+/// assert_eq!(a - b, Point::new(2, 2));
+/// assert_eq!(-a * 2, Point::new(-6, -8));
+/// assert_eq!(a.dot(&b), 11);
+/// assert_eq!(a.cross(&b), 2);
+/// assert_eq!(a.length(), 5.0);
+///
+/// let f: Point<f64> = a.into();
+/// assert_eq!(f.normalize(), Some(Point::new(0.6, 0.8)));
+/// assert_eq!(Point::<i32>::try_from(Point::new(2.6, -1.2)), Ok(Point::new(3, -1)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    // 结构体，方法也可以使用泛型
+    pub fn x(&self) -> &T {
+        &self.x
+    }
+
+    pub fn y(&self) -> &T {
+        &self.y
+    }
+}
+
+impl<T: Scalar> Point<T> {
+    pub fn origin() -> Point<T> {
+        Point::new(T::ZERO, T::ZERO)
+    }
+
+    /// 点积
+    pub fn dot(&self, other: &Point<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// 叉积的 z 分量，大于 0 表示 `other` 在逆时针方向
+    pub fn cross(&self, other: &Point<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// 作为向量的长度
+    pub fn length(&self) -> f64 {
+        self.x.to_f64().hypot(self.y.to_f64())
+    }
+
+    pub fn distance(&self, other: &Point<T>) -> f64 {
+        (self.x.to_f64() - other.x.to_f64()).hypot(self.y.to_f64() - other.y.to_f64())
+    }
+}
+
+macro_rules! impl_float_point {
+    ($($t:ty),*) => {
+        $(
+            impl Point<$t> {
+                /// 同方向的单位向量，零向量没有方向，返回 None
+                pub fn normalize(&self) -> Option<Point<$t>> {
+                    let length = self.x.hypot(self.y);
+                    if length == 0.0 || !length.is_finite() {
+                        return None;
+                    }
+                    Some(Point::new(self.x / length, self.y / length))
+                }
+            }
+        )*
+    };
+}
+
+impl_float_point!(f32, f64);
+
+impl<T: Scalar> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Scalar> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Scalar + Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// 数乘
+impl<T: Scalar> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Point<T> {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<Point<i32>> for Point<f64> {
+    fn from(p: Point<i32>) -> Point<f64> {
+        Point::new(p.x as f64, p.y as f64)
+    }
+}
+
+/// 浮点坐标无法表示为 `i32` 时的错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromPointError(());
+
+impl Display for TryFromPointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&tr("error.point.out_of_range", &[]))
+    }
+}
+
+impl std::error::Error for TryFromPointError {}
+
+/// 四舍五入到最近的整数坐标
+impl TryFrom<Point<f64>> for Point<i32> {
+    type Error = TryFromPointError;
+
+    fn try_from(p: Point<f64>) -> Result<Point<i32>, TryFromPointError> {
+        let round = |v: f64| {
+            let v = v.round();
+            if v.is_finite() && v >= i32::MIN as f64 && v <= i32::MAX as f64 {
+                Ok(v as i32)
+            } else {
+                Err(TryFromPointError(()))
+            }
+        };
+        Ok(Point::new(round(p.x)?, round(p.y)?))
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use super::{max, min, AreaScalar, Point, Scalar};
+
+/// 与坐标轴对齐的矩形，`origin` 是 x、y 都最小的那个角
+/// ```rust
+/// use rust_helloworld::geometry::{Point, Rectangle};
+///
+/// let a = Rectangle::at(Point::new(0, 0), 10, 10);
+/// let b = Rectangle::at(Point::new(5, 5), 10, 10);
+/// assert!(a.intersects(&b));
+/// assert_eq!(a.intersection(&b), Some(Rectangle::at(Point::new(5, 5), 5, 5)));
+/// assert_eq!(a.union(&b), Rectangle::at(Point::new(0, 0), 15, 15));
+/// assert!(a.contains(&Point::new(10, 3)));
+/// assert!(!a.can_hold(&b));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rectangle<T = u32> {
+    pub origin: Point<T>,
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: Scalar> Rectangle<T> {
+    /// 以原点为角的矩形
+    pub fn new(width: T, height: T) -> Rectangle<T> {
+        Rectangle::at(Point::origin(), width, height)
+    }
+
+    pub fn at(origin: Point<T>, width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    /// 由任意两个对角生成矩形
+    pub fn from_corners(a: Point<T>, b: Point<T>) -> Rectangle<T> {
+        let origin = Point::new(min(a.x, b.x), min(a.y, b.y));
+        let far = Point::new(max(a.x, b.x), max(a.y, b.y));
+        Rectangle::at(origin, far.x - origin.x, far.y - origin.y)
+    }
+
+    pub fn min_x(&self) -> T {
+        self.origin.x
+    }
+
+    pub fn min_y(&self) -> T {
+        self.origin.y
+    }
+
+    /// 右边的 x 坐标，整数超出 `T` 的范围时停在边界上
+    /// ```rust
+    /// use rust_helloworld::geometry::{Point, Rectangle};
+    ///
+    /// let r = Rectangle::at(Point::new(u32::MAX - 1, 0), 10, 10);
+    /// assert_eq!(r.max_x(), u32::MAX);
+    /// assert!(r.contains(&Point::new(u32::MAX, 5)));
+    /// ```
+    pub fn max_x(&self) -> T {
+        self.origin.x.saturating_add(self.width)
+    }
+
+    /// 下边的 y 坐标，整数超出 `T` 的范围时停在边界上
+    pub fn max_y(&self) -> T {
+        self.origin.y.saturating_add(self.height)
+    }
+
+    /// 面积，乘积溢出时和普通乘法一样，debug 下 panic，release 下回绕；
+    /// 不确定尺寸范围时请用 [`checked_area`](Rectangle::checked_area) 等方法
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
+
+    pub fn perimeter(&self) -> T {
+        let half = self.width + self.height;
+        half + half
+    }
+
+    /// 宽和高都比另一个矩形大时，才能把它装进去
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    /// 点在矩形内部或者边上
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        point.x >= self.min_x()
+            && point.x <= self.max_x()
+            && point.y >= self.min_y()
+            && point.y <= self.max_y()
+    }
+
+    /// 两个矩形有重叠的面积，只有边相接不算
+    pub fn intersects(&self, other: &Rectangle<T>) -> bool {
+        self.min_x() < other.max_x()
+            && other.min_x() < self.max_x()
+            && self.min_y() < other.max_y()
+            && other.min_y() < self.max_y()
+    }
+
+    /// 重叠的部分，没有重叠时返回 None
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Rectangle::from_corners(
+            Point::new(
+                max(self.min_x(), other.min_x()),
+                max(self.min_y(), other.min_y()),
+            ),
+            Point::new(
+                min(self.max_x(), other.max_x()),
+                min(self.max_y(), other.max_y()),
+            ),
+        ))
+    }
+
+    /// 同时包含两个矩形的最小矩形
+    pub fn union(&self, other: &Rectangle<T>) -> Rectangle<T> {
+        Rectangle::from_corners(
+            Point::new(
+                min(self.min_x(), other.min_x()),
+                min(self.min_y(), other.min_y()),
+            ),
+            Point::new(
+                max(self.max_x(), other.max_x()),
+                max(self.max_y(), other.max_y()),
+            ),
+        )
+    }
+}
+
+impl<T: AreaScalar> Rectangle<T> {
+    /// 面积，溢出时返回 None
+    /// ```rust
+    /// use rust_helloworld::geometry::Rectangle;
+    ///
+    /// let big = Rectangle::new(u32::MAX, 2);
+    /// assert_eq!(big.checked_area(), None);
+    /// assert_eq!(big.saturating_area(), u32::MAX);
+    /// assert_eq!(big.wide_area(), u32::MAX as u64 * 2);
+    /// ```
+    pub fn checked_area(&self) -> Option<T> {
+        self.width.checked_mul(self.height)
+    }
+
+    /// 面积，溢出时取类型的最大值
+    pub fn saturating_area(&self) -> T {
+        self.width.saturating_mul(self.height)
+    }
+
+    /// 用加宽后的类型计算面积，不会溢出
+    pub fn wide_area(&self) -> T::Wide {
+        self.width.widening_mul(self.height)
+    }
+}
@@ -0,0 +1,323 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Point, Rectangle, Scalar, Transform};
+
+/// 圆转成多边形时使用的边数
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// 平面图形的公共行为，统一使用 `f64` 坐标
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> Rectangle<f64>;
+    /// 点在图形内部或者边上
+    fn contains(&self, point: &Point<f64>) -> bool;
+    /// 用多边形表示这个图形，曲线会被近似
+    fn to_polygon(&self) -> Polygon;
+
+    /// 对图形做仿射变换
+    fn transformed(self, transform: Transform) -> Transformed<Self>
+    where
+        Self: Sized,
+    {
+        Transformed {
+            shape: self,
+            transform,
+        }
+    }
+}
+
+fn bounds_of(points: &[Point<f64>]) -> Rectangle<f64> {
+    let mut iter = points.iter();
+    let first = match iter.next() {
+        Some(p) => *p,
+        None => return Rectangle::new(0.0, 0.0),
+    };
+    let (mut min, mut max) = (first, first);
+    for p in iter {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    Rectangle::from_corners(min, max)
+}
+
+impl<T: Scalar> Shape for Rectangle<T> {
+    fn area(&self) -> f64 {
+        self.width.to_f64() * self.height.to_f64()
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width.to_f64() + self.height.to_f64())
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        Rectangle::from_corners(
+            Point::new(self.min_x().to_f64(), self.min_y().to_f64()),
+            Point::new(self.max_x().to_f64(), self.max_y().to_f64()),
+        )
+    }
+
+    fn contains(&self, point: &Point<f64>) -> bool {
+        self.bounding_box().contains(point)
+    }
+
+    fn to_polygon(&self) -> Polygon {
+        let b = self.bounding_box();
+        Polygon::new(vec![
+            Point::new(b.min_x(), b.min_y()),
+            Point::new(b.max_x(), b.min_y()),
+            Point::new(b.max_x(), b.max_y()),
+            Point::new(b.min_x(), b.max_y()),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Circle {
+    pub center: Point<f64>,
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point<f64>, radius: f64) -> Circle {
+        Circle { center, radius }
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        let r = Point::new(self.radius, self.radius);
+        Rectangle::from_corners(self.center - r, self.center + r)
+    }
+
+    fn contains(&self, point: &Point<f64>) -> bool {
+        self.center.distance(point) <= self.radius
+    }
+
+    fn to_polygon(&self) -> Polygon {
+        let points = (0..CIRCLE_SEGMENTS)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / CIRCLE_SEGMENTS as f64;
+                let (sin, cos) = angle.sin_cos();
+                self.center + Point::new(cos, sin) * self.radius
+            })
+            .collect();
+        Polygon::new(points)
+    }
+}
+
+/// 三角形，边上的点也算在内部；三点共线时退化成线段，只包含线段上的点
+/// ```rust
+/// use rust_helloworld::geometry::{Point, Shape, Triangle};
+///
+/// let t = Triangle::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0), Point::new(0.0, 4.0));
+/// assert_eq!(t.area(), 8.0);
+/// assert!(t.contains(&Point::new(1.0, 1.0)));
+/// assert!(t.contains(&Point::new(2.0, 2.0)));
+/// assert!(!t.contains(&Point::new(3.0, 3.0)));
+///
+/// let p = Point::new(1.0, 1.0);
+/// let dot = Triangle::new(p, p, p);
+/// assert!(dot.contains(&p));
+/// assert!(!dot.contains(&Point::new(100.0, -50.0)));
+///
+/// let line = Triangle::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0), Point::new(2.0, 2.0));
+/// assert!(line.contains(&Point::new(1.5, 1.5)));
+/// assert!(!line.contains(&Point::new(50.0, 50.0)));
+/// assert!(!line.contains(&Point::new(1.0, 0.0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Triangle {
+    pub a: Point<f64>,
+    pub b: Point<f64>,
+    pub c: Point<f64>,
+}
+
+impl Triangle {
+    pub fn new(a: Point<f64>, b: Point<f64>, c: Point<f64>) -> Triangle {
+        Triangle { a, b, c }
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        ((self.b - self.a).cross(&(self.c - self.a)) / 2.0).abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.a.distance(&self.b) + self.b.distance(&self.c) + self.c.distance(&self.a)
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        bounds_of(&[self.a, self.b, self.c])
+    }
+
+    fn contains(&self, point: &Point<f64>) -> bool {
+        // 面积为 0 时每个点都在边的"同一侧"，改为判断是否在某条边上
+        if self.area() <= EPSILON {
+            return [(self.a, self.b), (self.b, self.c), (self.c, self.a)]
+                .into_iter()
+                .any(|(p, q)| on_segment(p, q, point));
+        }
+        // 点在三条边的同一侧
+        let d1 = (self.b - self.a).cross(&(*point - self.a));
+        let d2 = (self.c - self.b).cross(&(*point - self.b));
+        let d3 = (self.a - self.c).cross(&(*point - self.c));
+        let negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(negative && positive)
+    }
+
+    fn to_polygon(&self) -> Polygon {
+        Polygon::new(vec![self.a, self.b, self.c])
+    }
+}
+
+/// 简单多边形（边不自相交），顶点按顺序排列，首尾自动闭合
+/// ```rust
+/// use rust_helloworld::geometry::{Point, Polygon, Shape, Transform};
+///
+/// let l_shape = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(2.0, 0.0),
+///     Point::new(2.0, 1.0),
+///     Point::new(1.0, 1.0),
+///     Point::new(1.0, 2.0),
+///     Point::new(0.0, 2.0),
+/// ]);
+/// assert_eq!(l_shape.area(), 3.0);
+/// assert_eq!(l_shape.perimeter(), 8.0);
+/// assert!(l_shape.contains(&Point::new(0.5, 1.5)));
+/// assert!(!l_shape.contains(&Point::new(1.5, 1.5)));
+///
+/// let moved = l_shape.transformed(Transform::scale(2.0, 2.0).then(&Transform::translate(5.0, 0.0)));
+/// assert_eq!(moved.area(), 12.0);
+/// assert!(moved.contains(&Point::new(6.0, 3.0)));
+/// assert_eq!(moved.bounding_box().origin, Point::new(5.0, 0.0));
+///
+/// // 边上的点也算在内部，包括右边和上边
+/// let square = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(1.0, 0.0),
+///     Point::new(1.0, 1.0),
+///     Point::new(0.0, 1.0),
+/// ]);
+/// assert!(square.contains(&Point::new(1.0, 0.5)));
+/// assert!(square.contains(&Point::new(0.5, 1.0)));
+/// assert!(square.contains(&Point::new(1.0, 1.0)));
+/// assert!(square.contains(&Point::new(0.0, 0.0)));
+/// assert!(!square.contains(&Point::new(1.0, 1.5)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Polygon {
+    pub points: Vec<Point<f64>>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Point<f64>>) -> Polygon {
+        Polygon { points }
+    }
+
+    /// 依次返回每条边的两个端点
+    pub fn edges(&self) -> impl Iterator<Item = (Point<f64>, Point<f64>)> + '_ {
+        let n = self.points.len();
+        (0..n).map(move |i| (self.points[i], self.points[(i + 1) % n]))
+    }
+
+    pub fn transform(&self, transform: &Transform) -> Polygon {
+        Polygon::new(self.points.iter().map(|p| transform.apply(*p)).collect())
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        // 鞋带公式
+        (self.edges().map(|(p, q)| p.cross(&q)).sum::<f64>() / 2.0).abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|(p, q)| p.distance(&q)).sum()
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        bounds_of(&self.points)
+    }
+
+    fn contains(&self, point: &Point<f64>) -> bool {
+        // 射线法只包含左边和下边，边上的点先单独判断
+        if self.edges().any(|(p, q)| on_segment(p, q, point)) {
+            return true;
+        }
+        // 射线法：向右的射线与边相交奇数次时在内部
+        let mut inside = false;
+        for (p, q) in self.edges() {
+            if (p.y > point.y) != (q.y > point.y) {
+                let x = p.x + (point.y - p.y) / (q.y - p.y) * (q.x - p.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    fn to_polygon(&self) -> Polygon {
+        self.clone()
+    }
+}
+
+/// 判断边上的点时允许的误差
+const EPSILON: f64 = 1e-9;
+
+/// 点是否在线段 pq 上：与 pq 共线，并且落在 pq 的范围内
+fn on_segment(p: Point<f64>, q: Point<f64>, point: &Point<f64>) -> bool {
+    let (d, v) = (q - p, *point - p);
+    if d.cross(&v).abs() > EPSILON * d.length().max(1.0) {
+        return false;
+    }
+    let within = |a: f64, b: f64, x: f64| a.min(b) - EPSILON <= x && x <= a.max(b) + EPSILON;
+    within(p.x, q.x, point.x) && within(p.y, q.y, point.y)
+}
+
+/// 做过仿射变换的图形，由 [`Shape::transformed`] 生成
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transformed<S> {
+    pub shape: S,
+    pub transform: Transform,
+}
+
+impl<S: Shape> Shape for Transformed<S> {
+    fn area(&self) -> f64 {
+        self.shape.area() * self.transform.determinant().abs()
+    }
+
+    // 非等比缩放后曲线不再保持原来的形状，周长按多边形近似计算
+    fn perimeter(&self) -> f64 {
+        self.to_polygon().perimeter()
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        self.to_polygon().bounding_box()
+    }
+
+    fn contains(&self, point: &Point<f64>) -> bool {
+        match self.transform.inverse() {
+            Some(inverse) => self.shape.contains(&inverse.apply(*point)),
+            None => false,
+        }
+    }
+
+    fn to_polygon(&self) -> Polygon {
+        self.shape.to_polygon().transform(&self.transform)
+    }
+}
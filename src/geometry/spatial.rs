@@ -0,0 +1,328 @@
+use super::{Point, Rectangle, Scalar};
+
+/// 每个节点最多直接存放的条目数，超过后再细分
+const NODE_CAPACITY: usize = 8;
+const MAX_DEPTH: usize = 10;
+
+/// [`QuadTree`] 中条目的编号
+///
+/// 位置被复用时代数加一，删除之后留下的旧编号不会指向新的条目。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ItemId {
+    index: usize,
+    generation: u32,
+}
+
+struct Entry<T, V> {
+    rect: Rectangle<T>,
+    value: V,
+}
+
+// 节点范围统一用 f64 表示，方便对整数坐标取中点
+struct Node {
+    bounds: Rectangle<f64>,
+    items: Vec<usize>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+fn to_f64<T: Scalar>(rect: &Rectangle<T>) -> Rectangle<f64> {
+    Rectangle::at(
+        Point::new(rect.origin.x.to_f64(), rect.origin.y.to_f64()),
+        rect.width.to_f64(),
+        rect.height.to_f64(),
+    )
+}
+
+/// 包括边相接在内的重叠，点（零面积的矩形）也能被查到
+fn touches(a: &Rectangle<f64>, b: &Rectangle<f64>) -> bool {
+    a.min_x() <= b.max_x()
+        && b.min_x() <= a.max_x()
+        && a.min_y() <= b.max_y()
+        && b.min_y() <= a.max_y()
+}
+
+fn encloses(outer: &Rectangle<f64>, inner: &Rectangle<f64>) -> bool {
+    inner.min_x() >= outer.min_x()
+        && inner.max_x() <= outer.max_x()
+        && inner.min_y() >= outer.min_y()
+        && inner.max_y() <= outer.max_y()
+}
+
+/// 点到矩形的距离，点在矩形内时为 0
+fn distance(rect: &Rectangle<f64>, point: &Point<f64>) -> f64 {
+    let dx = (rect.min_x() - point.x)
+        .max(point.x - rect.max_x())
+        .max(0.0);
+    let dy = (rect.min_y() - point.y)
+        .max(point.y - rect.max_y())
+        .max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+impl Node {
+    fn new(bounds: Rectangle<f64>) -> Node {
+        Node {
+            bounds,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn split(&mut self) {
+        let w = self.bounds.width / 2.0;
+        let h = self.bounds.height / 2.0;
+        let (x, y) = (self.bounds.min_x(), self.bounds.min_y());
+        self.children = Some(Box::new([
+            Node::new(Rectangle::at(Point::new(x, y), w, h)),
+            Node::new(Rectangle::at(Point::new(x + w, y), w, h)),
+            Node::new(Rectangle::at(Point::new(x, y + h), w, h)),
+            Node::new(Rectangle::at(Point::new(x + w, y + h), w, h)),
+        ]));
+    }
+
+    /// 能完整装下该矩形的子节点
+    fn child_for(&mut self, rect: &Rectangle<f64>) -> Option<&mut Node> {
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find(|child| encloses(&child.bounds, rect))
+    }
+
+    fn insert(
+        &mut self,
+        index: usize,
+        rect: &Rectangle<f64>,
+        rects: &dyn Fn(usize) -> Rectangle<f64>,
+        depth: usize,
+    ) {
+        if let Some(child) = self.child_for(rect) {
+            return child.insert(index, rect, rects, depth + 1);
+        }
+        self.items.push(index);
+        if self.children.is_none() && self.items.len() > NODE_CAPACITY && depth < MAX_DEPTH {
+            self.split();
+            // 把能下放的条目移到子节点
+            let items = std::mem::take(&mut self.items);
+            for item in items {
+                let item_rect = rects(item);
+                match self.child_for(&item_rect) {
+                    Some(child) => child.insert(item, &item_rect, rects, depth + 1),
+                    None => self.items.push(item),
+                }
+            }
+        }
+    }
+
+    // 条目一定在插入时走到的那个节点上
+    fn remove(&mut self, index: usize, rect: &Rectangle<f64>) {
+        if let Some(position) = self.items.iter().position(|&item| item == index) {
+            self.items.swap_remove(position);
+        } else if let Some(child) = self.child_for(rect) {
+            child.remove(index, rect);
+        }
+    }
+
+    fn query(
+        &self,
+        area: &Rectangle<f64>,
+        rects: &dyn Fn(usize) -> Rectangle<f64>,
+        found: &mut Vec<usize>,
+    ) {
+        found.extend(
+            self.items
+                .iter()
+                .filter(|&&item| touches(&rects(item), area)),
+        );
+        if let Some(children) = &self.children {
+            for child in children.iter().filter(|child| touches(&child.bounds, area)) {
+                child.query(area, rects, found);
+            }
+        }
+    }
+
+    fn nearest(
+        &self,
+        point: &Point<f64>,
+        rects: &dyn Fn(usize) -> Rectangle<f64>,
+        best: &mut Option<(usize, f64)>,
+    ) {
+        for &item in &self.items {
+            let d = distance(&rects(item), point);
+            if best.is_none_or(|(_, best_d)| d < best_d) {
+                *best = Some((item, d));
+            }
+        }
+        if let Some(children) = &self.children {
+            let mut order: Vec<(f64, &Node)> = children
+                .iter()
+                .map(|child| (distance(&child.bounds, point), child))
+                .collect();
+            order.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for (d, child) in order {
+                // 子节点里的条目不会比子节点的范围更近
+                if best.is_none_or(|(_, best_d)| d < best_d) {
+                    child.nearest(point, rects, best);
+                }
+            }
+        }
+    }
+}
+
+/// 矩形和点的四叉树索引
+///
+/// 超出 `bounds` 的条目也可以插入，它们会留在根节点上，只是查询时无法剪枝。
+/// 点可以用宽高为 0 的矩形表示。删除后空出的位置会被之后插入的条目复用，
+/// 但新条目的编号与旧的不同，用旧编号 `get` 或 `remove` 都会返回 None。
+/// ```rust
+/// use rust_helloworld::geometry::{Point, QuadTree, Rectangle};
+///
+/// let mut tree = QuadTree::new(Rectangle::new(100, 100));
+/// let a = tree.insert(Rectangle::at(Point::new(10, 10), 5, 5), "a");
+/// let b = tree.insert(Rectangle::at(Point::new(12, 12), 5, 5), "b");
+/// let c = tree.insert(Rectangle::at(Point::new(80, 80), 0, 0), "c");
+///
+/// let mut hits = tree.query(&Rectangle::at(Point::new(0, 0), 11, 11));
+/// hits.sort();
+/// assert_eq!(hits, vec![a]);
+/// assert_eq!(tree.nearest(&Point::new(90, 90)), Some(c));
+/// assert_eq!(tree.overlapping_pairs(), vec![(a, b)]);
+///
+/// assert_eq!(tree.remove(c).map(|(_, v)| v), Some("c"));
+/// assert_eq!(tree.nearest(&Point::new(90, 90)), Some(b));
+///
+/// // 复用 c 空出的位置，旧编号不会指向 d
+/// let d = tree.insert(Rectangle::at(Point::new(50, 50), 1, 1), "d");
+/// assert_ne!(d, c);
+/// assert!(tree.get(c).is_none());
+/// assert!(tree.remove(c).is_none());
+/// assert_eq!(tree.get(d).map(|(_, v)| *v), Some("d"));
+/// assert_eq!(tree.len(), 3);
+/// ```
+pub struct QuadTree<T, V> {
+    root: Node,
+    entries: Vec<Option<Entry<T, V>>>,
+    /// 每个位置当前的代数，删除条目时加一
+    generations: Vec<u32>,
+    /// 已删除条目空出的位置
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T: Scalar, V> QuadTree<T, V> {
+    pub fn new(bounds: Rectangle<T>) -> QuadTree<T, V> {
+        QuadTree {
+            root: Node::new(to_f64(&bounds)),
+            entries: Vec::new(),
+            generations: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn id(&self, index: usize) -> ItemId {
+        ItemId {
+            index,
+            generation: self.generations[index],
+        }
+    }
+
+    /// 编号指向的条目还在时返回其位置
+    fn index_of(&self, id: ItemId) -> Option<usize> {
+        match self.entries.get(id.index) {
+            Some(Some(_)) if self.generations[id.index] == id.generation => Some(id.index),
+            _ => None,
+        }
+    }
+
+    fn rect_of(&self, index: usize) -> Rectangle<f64> {
+        match &self.entries[index] {
+            Some(entry) => to_f64(&entry.rect),
+            None => unreachable!("tree only references live entries"),
+        }
+    }
+
+    pub fn insert(&mut self, rect: Rectangle<T>, value: V) -> ItemId {
+        let bounds = to_f64(&rect);
+        let entry = Some(Entry { rect, value });
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.entries[index] = entry;
+                index
+            }
+            None => {
+                self.entries.push(entry);
+                self.generations.push(0);
+                self.entries.len() - 1
+            }
+        };
+        self.len += 1;
+
+        let entries = &self.entries;
+        let rects = |i: usize| match &entries[i] {
+            Some(entry) => to_f64(&entry.rect),
+            None => unreachable!("tree only references live entries"),
+        };
+        self.root.insert(index, &bounds, &rects, 0);
+        self.id(index)
+    }
+
+    pub fn remove(&mut self, id: ItemId) -> Option<(Rectangle<T>, V)> {
+        let index = self.index_of(id)?;
+        let rect = self.rect_of(index);
+        self.root.remove(index, &rect);
+        self.len -= 1;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(index);
+        self.entries[index]
+            .take()
+            .map(|entry| (entry.rect, entry.value))
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<(&Rectangle<T>, &V)> {
+        let entry = self.entries[self.index_of(id)?].as_ref()?;
+        Some((&entry.rect, &entry.value))
+    }
+
+    /// 与 `area` 有接触（包括边相接）的所有条目
+    pub fn query(&self, area: &Rectangle<T>) -> Vec<ItemId> {
+        let mut found = Vec::new();
+        self.root
+            .query(&to_f64(area), &|i| self.rect_of(i), &mut found);
+        found.into_iter().map(|index| self.id(index)).collect()
+    }
+
+    /// 离 `point` 最近的条目，点在矩形内部时距离为 0
+    pub fn nearest(&self, point: &Point<T>) -> Option<ItemId> {
+        let point = Point::new(point.x.to_f64(), point.y.to_f64());
+        let mut best = None;
+        self.root.nearest(&point, &|i| self.rect_of(i), &mut best);
+        best.map(|(index, _)| self.id(index))
+    }
+
+    /// 所有真正重叠（有重叠面积）的条目对，每对中编号小的在前，按编号排序
+    pub fn overlapping_pairs(&self) -> Vec<(ItemId, ItemId)> {
+        let mut pairs = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let entry = match entry {
+                Some(entry) => entry,
+                None => continue,
+            };
+            for other in self.query(&entry.rect) {
+                if other.index > index && self.rect_of(other.index).intersects(&to_f64(&entry.rect))
+                {
+                    pairs.push((self.id(index), other));
+                }
+            }
+        }
+        pairs.sort();
+        pairs
+    }
+}
@@ -0,0 +1,132 @@
+use std::ops::Mul;
+
+use serde::{Deserialize, Serialize};
+
+use super::Point;
+
+/// 二维仿射变换，和 SVG 的 `matrix(a b c d e f)` 含义相同：
+/// `x' = a*x + c*y + e`，`y' = b*x + d*y + f`
+/// ```rust
+/// use std::f64::consts::FRAC_PI_2;
+/// use rust_helloworld::geometry::{Point, Transform};
+///
+/// let t = Transform::rotate(FRAC_PI_2).then(&Transform::translate(10.0, 0.0));
+/// let p = t.apply(Point::new(1.0, 0.0));
+/// assert!((p.x - 10.0).abs() < 1e-9 && (p.y - 1.0).abs() < 1e-9);
+///
+/// let back = t.inverse().unwrap().apply(p);
+/// assert!((back.x - 1.0).abs() < 1e-9 && back.y.abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn translate(dx: f64, dy: f64) -> Transform {
+        Transform {
+            e: dx,
+            f: dy,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Transform {
+        Transform {
+            a: sx,
+            d: sy,
+            ..Transform::identity()
+        }
+    }
+
+    /// 绕原点逆时针旋转，单位为弧度
+    pub fn rotate(angle: f64) -> Transform {
+        let (sin, cos) = angle.sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// 绕指定的点旋转
+    pub fn rotate_about(angle: f64, center: Point<f64>) -> Transform {
+        Transform::translate(-center.x, -center.y)
+            .then(&Transform::rotate(angle))
+            .then(&Transform::translate(center.x, center.y))
+    }
+
+    /// 先做当前变换，再做 `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// 面积的缩放比例（带符号）
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// 逆变换，不可逆（把平面压成一条线或者一个点）时返回 None
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Transform {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    pub fn apply(&self, p: Point<f64>) -> Point<f64> {
+        Point::new(
+            self.a * p.x + self.c * p.y + self.e,
+            self.b * p.x + self.d * p.y + self.f,
+        )
+    }
+}
+
+/// `a * b` 表示先做 `b` 再做 `a`，与矩阵乘法的顺序一致
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Transform {
+        rhs.then(&self)
+    }
+}
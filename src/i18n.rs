@@ -0,0 +1,265 @@
+//! 本地化：面向用户的文字都从消息目录中按当前语言取出
+//!
+//! 目录在 `locales/<语言>.json` 中，编译时嵌入。值是一段文字，或者按数量区分单复数的
+//! `{"one": ..., "other": ...}`，文字中的 `{name}` 会被替换成同名参数，`{{` 和 `}}`
+//! 输出花括号本身。
+//!
+//! 当前语言可以用 [`set_locale`] 设置，没有设置时取环境变量 `ART_LOCALE`，缺省为美式英文。
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+/// 目录中找不到时使用的语言
+const FALLBACK: Locale = Locale::EnUs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 按语言部分识别，`zh`、`zh_CN.UTF-8`、`en-GB` 都可以
+    /// ```rust
+    /// use rust_helloworld::i18n::Locale;
+    ///
+    /// assert_eq!(Locale::parse("zh_CN.UTF-8"), Some(Locale::ZhCn));
+    /// assert_eq!(Locale::parse("EN"), Some(Locale::EnUs));
+    /// assert_eq!(Locale::parse("fr-FR"), None);
+    /// ```
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_', '.']).next()?.to_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 环境变量 `ART_LOCALE` 指定的语言，没有或者不认识时为美式英文
+    pub fn from_env() -> Locale {
+        env::var("ART_LOCALE")
+            .ok()
+            .and_then(|tag| Locale::parse(&tag))
+            .unwrap_or(Locale::EnUs)
+    }
+
+    /// 复数规则：中文不区分单复数，英文只有 1 是单数
+    pub fn plural(&self, count: u64) -> Plural {
+        match self {
+            Locale::ZhCn => Plural::Other,
+            Locale::EnUs if count == 1 => Plural::One,
+            Locale::EnUs => Plural::Other,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => include_str!("../locales/zh-CN.json"),
+            Locale::EnUs => include_str!("../locales/en-US.json"),
+        }
+    }
+}
+
+/// 复数类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plural {
+    One,
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Message {
+    Text(String),
+    Plural { one: Option<String>, other: String },
+}
+
+impl Message {
+    fn select(&self, plural: Plural) -> &str {
+        match (self, plural) {
+            (Message::Text(text), _) => text,
+            (Message::Plural { one: Some(one), .. }, Plural::One) => one,
+            (Message::Plural { other, .. }, _) => other,
+        }
+    }
+}
+
+/// 消息目录
+/// ```rust
+/// use rust_helloworld::i18n::{Catalog, Locale};
+///
+/// let catalog = Catalog::builtin();
+/// let greeting = catalog.format(Locale::EnUs, "seating.greeting", &[]);
+/// assert_eq!(greeting, "sit down please");
+/// assert_eq!(catalog.format(Locale::ZhCn, "seating.greeting", &[]), "请坐");
+///
+/// let args: &[(&str, &dyn std::fmt::Display)] = &[("id", &3), ("name", &"Ann")];
+/// assert_eq!(catalog.format_count(Locale::EnUs, "cli.party", 1, args), "#3 Ann (1 guest)");
+/// assert_eq!(catalog.format_count(Locale::EnUs, "cli.party", 4, args), "#3 Ann (4 guests)");
+/// assert_eq!(catalog.format_count(Locale::ZhCn, "cli.party", 4, args), "#3 Ann（4 位）");
+///
+/// // 找不到的消息原样输出键
+/// assert_eq!(catalog.format(Locale::ZhCn, "no.such.key", &[]), "no.such.key");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<Locale, HashMap<String, Message>>,
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    /// 内置的目录，第一次使用时解析
+    pub fn builtin() -> &'static Catalog {
+        static BUILTIN: OnceLock<Catalog> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut catalog = Catalog::new();
+            for locale in Locale::ALL {
+                catalog
+                    .load(locale, locale.source())
+                    .expect("builtin catalog is valid json");
+            }
+            catalog
+        })
+    }
+
+    /// 从 JSON 加入一种语言的消息，已有的键会被覆盖
+    pub fn load(&mut self, locale: Locale, json: &str) -> Result<(), serde_json::Error> {
+        let messages: HashMap<String, Message> = serde_json::from_str(json)?;
+        self.messages.entry(locale).or_default().extend(messages);
+        Ok(())
+    }
+
+    pub fn insert(&mut self, locale: Locale, key: &str, text: &str) {
+        self.messages
+            .entry(locale)
+            .or_default()
+            .insert(key.to_string(), Message::Text(text.to_string()));
+    }
+
+    fn message(&self, locale: Locale, key: &str) -> Option<&Message> {
+        [locale, FALLBACK]
+            .iter()
+            .find_map(|locale| self.messages.get(locale)?.get(key))
+    }
+
+    pub fn format(&self, locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        match self.message(locale, key) {
+            Some(message) => interpolate(message.select(Plural::Other), args),
+            None => key.to_string(),
+        }
+    }
+
+    /// 按 `count` 选择单复数，`count` 也可以用 `{count}` 引用
+    pub fn format_count(
+        &self,
+        locale: Locale,
+        key: &str,
+        count: u64,
+        args: &[(&str, &dyn Display)],
+    ) -> String {
+        let message = match self.message(locale, key) {
+            Some(message) => message,
+            None => return key.to_string(),
+        };
+        let mut all: Vec<(&str, &dyn Display)> = vec![("count", &count)];
+        all.extend_from_slice(args);
+        interpolate(message.select(locale.plural(count)), &all)
+    }
+}
+
+/// 替换 `{name}`，没有对应参数的占位符原样保留
+/// ```rust
+/// use rust_helloworld::i18n::interpolate;
+///
+/// assert_eq!(interpolate("{a} + {b} = {c}", &[("a", &1), ("b", &"2")]), "1 + 2 = {c}");
+/// assert_eq!(interpolate("{{a}} {a", &[("a", &1)]), "{a} {a");
+/// ```
+pub fn interpolate(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        let placeholder = tail
+            .strip_prefix('{')
+            .and_then(|inner| Some(&inner[..inner.find('}')?]));
+        let arg = placeholder.and_then(|name| args.iter().find(|(n, _)| *n == name));
+        match (placeholder, arg) {
+            (Some(name), Some((_, value))) => {
+                out.push_str(&value.to_string());
+                rest = &tail[name.len() + 2..];
+            }
+            _ => {
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 0 表示还没有设置，第一次读取时从环境变量初始化
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// 当前语言
+pub fn locale() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::ZhCn,
+        2 => Locale::EnUs,
+        _ => {
+            let locale = Locale::from_env();
+            set_locale(locale);
+            locale
+        }
+    }
+}
+
+/// 设置当前语言，对所有线程生效
+pub fn set_locale(locale: Locale) {
+    let value = match locale {
+        Locale::ZhCn => 1,
+        Locale::EnUs => 2,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+/// 用当前语言格式化内置目录中的消息
+/// ```rust
+/// use rust_helloworld::i18n::{set_locale, tr, Locale};
+///
+/// set_locale(Locale::EnUs);
+/// assert_eq!(tr("cli.order_not_found", &[("id", &7)]), "order #7 not found");
+/// set_locale(Locale::ZhCn);
+/// assert_eq!(tr("cli.order_not_found", &[("id", &7)]), "找不到订单 #7");
+/// ```
+pub fn tr(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    Catalog::builtin().format(locale(), key, args)
+}
+
+/// 用当前语言格式化区分单复数的消息
+pub fn tr_count(key: &str, count: u64, args: &[(&str, &dyn Display)]) -> String {
+    Catalog::builtin().format_count(locale(), key, count, args)
+}
@@ -2,25 +2,57 @@
 //!
 //!  未来的艺术建模库，现在的调色库
 //!
-mod back_of_house;
+pub mod action;
+pub mod back_of_house;
+pub mod canvas;
+pub mod classroom;
+pub mod cli;
+pub mod content;
+mod csv;
+pub mod draw;
+pub mod events;
 pub mod front_of_house;
+pub mod geometry;
+pub mod i18n;
+pub mod message;
+pub mod notify;
+pub mod report;
+pub mod script;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod storage;
+pub mod turtle;
 
 pub fn eat_at_restaurant() -> String {
-    front_of_house::hosting::add_to_waitlist();
+    // 内存存储的操作不会失败
+    let mut store = storage::MemoryStorage::new();
+    let party = front_of_house::hosting::add_to_waitlist(&mut store, "guest", 2).unwrap();
+    let seating = front_of_house::hosting::seat_at_table(&mut store, party.id, 1)
+        .unwrap()
+        .unwrap();
+    let items = vec![front_of_house::serving::OrderItem::new(
+        "noodles", "main", 1800, 2,
+    )];
+    let order =
+        front_of_house::serving::take_order(&mut store, "waiter", seating.table, party.size, items)
+            .unwrap();
 
-    back_of_house::cook_order();
+    back_of_house::cook_order(&mut store, "chef", order.id).unwrap();
 
-    String::from("yummy yummy!")
+    i18n::tr("restaurant.yummy", &[])
 }
 
 pub use self::kinds::PrimaryColor;
+pub use self::kinds::Rgba;
 pub use self::kinds::SecondaryColor;
 pub use self::utils::mix;
 
 pub mod kinds {
     //! 定义颜色的类型
+    use serde::{Deserialize, Serialize};
 
     /// 主色
+    #[derive(Debug, Clone, Copy, PartialEq)]
     pub enum PrimaryColor {
         Red,
         Yellow,
@@ -34,19 +66,101 @@ pub mod kinds {
         Green,
         Purple,
     }
+
+    /// 带透明度的颜色，`a` 为 0 时完全透明，255 时完全不透明
+    /// ```rust
+    /// use rust_helloworld::kinds::{PrimaryColor, Rgba, SecondaryColor};
+    /// assert_eq!(Rgba::from(PrimaryColor::Red), Rgba::rgb(255, 0, 0));
+    /// assert_eq!(Rgba::from(SecondaryColor::Green).with_alpha(128).a, 128);
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+    pub struct Rgba {
+        pub r: u8,
+        pub g: u8,
+        pub b: u8,
+        pub a: u8,
+    }
+
+    impl Rgba {
+        pub const TRANSPARENT: Rgba = Rgba::new(0, 0, 0, 0);
+        pub const BLACK: Rgba = Rgba::rgb(0, 0, 0);
+        pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+
+        pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Rgba {
+            Rgba { r, g, b, a }
+        }
+
+        /// 不透明的颜色
+        pub const fn rgb(r: u8, g: u8, b: u8) -> Rgba {
+            Rgba::new(r, g, b, 255)
+        }
+
+        pub fn with_alpha(self, a: u8) -> Rgba {
+            Rgba { a, ..self }
+        }
+    }
+
+    impl From<PrimaryColor> for Rgba {
+        fn from(color: PrimaryColor) -> Rgba {
+            match color {
+                PrimaryColor::Red => Rgba::rgb(255, 0, 0),
+                PrimaryColor::Yellow => Rgba::rgb(255, 255, 0),
+                PrimaryColor::Blue => Rgba::rgb(0, 0, 255),
+            }
+        }
+    }
+
+    impl From<SecondaryColor> for Rgba {
+        fn from(color: SecondaryColor) -> Rgba {
+            match color {
+                SecondaryColor::Orange => Rgba::rgb(255, 128, 0),
+                SecondaryColor::Green => Rgba::rgb(0, 128, 0),
+                SecondaryColor::Purple => Rgba::rgb(128, 0, 128),
+            }
+        }
+    }
 }
 
 pub mod utils {
-    //! 实用工具，目前只实现了调色板
+    //! 实用工具：调色板和时钟
+    use std::time::{SystemTime, UNIX_EPOCH};
+
     use crate::kinds::*;
 
+    /// 当前的 unix 时间戳（秒）
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
     /// 将两种主色调成副色
     /// ```rust
     /// use rust_helloworld::utils::mix;
     /// use rust_helloworld::kinds::{PrimaryColor,SecondaryColor};
     /// assert!(matches!(mix(PrimaryColor::Yellow, PrimaryColor::Blue), SecondaryColor::Green));
     /// ```
+    ///
+    /// 两种颜色相同时调不出副色，仍然返回绿色，需要区分时用 [`try_mix`]
     pub fn mix(c1: PrimaryColor, c2: PrimaryColor) -> SecondaryColor {
-        SecondaryColor::Green
+        try_mix(c1, c2).unwrap_or(SecondaryColor::Green)
+    }
+
+    /// 将两种主色调成副色，两种颜色相同时调不出副色，返回 None
+    /// ```rust
+    /// use rust_helloworld::utils::try_mix;
+    /// use rust_helloworld::kinds::{PrimaryColor, SecondaryColor};
+    /// assert_eq!(try_mix(PrimaryColor::Blue, PrimaryColor::Red), Some(SecondaryColor::Purple));
+    /// assert_eq!(try_mix(PrimaryColor::Red, PrimaryColor::Red), None);
+    /// ```
+    pub fn try_mix(c1: PrimaryColor, c2: PrimaryColor) -> Option<SecondaryColor> {
+        use PrimaryColor::*;
+        match (c1, c2) {
+            (Red, Yellow) | (Yellow, Red) => Some(SecondaryColor::Orange),
+            (Yellow, Blue) | (Blue, Yellow) => Some(SecondaryColor::Green),
+            (Red, Blue) | (Blue, Red) => Some(SecondaryColor::Purple),
+            _ => None,
+        }
     }
 }
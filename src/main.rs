@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::fmt::{Debug, Display, Error, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::{ErrorKind, Read};
 use std::ops::Mul;
@@ -7,10 +7,25 @@ use std::{io, ops};
 
 use rand::Rng;
 
-use rust_helloworld::front_of_house::hosting::seat_at_table;
-use rust_helloworld::{eat_at_restaurant, mix, PrimaryColor};
+use rust_helloworld::action::{Action, Interpreter};
+use rust_helloworld::classroom::{Gradebook, Hello, Roster, Student, Teacher};
+use rust_helloworld::content::{Feed, Html, Markdown, Post, SearchIndex, Summary, Tweet};
+use rust_helloworld::draw::{render, Draw, DrawContext, Style, TextBuffer};
+use rust_helloworld::front_of_house::hosting::{add_to_waitlist, seat_at_table};
+use rust_helloworld::geometry::{Circle, Point, Rectangle};
+use rust_helloworld::i18n::{self, Locale};
+use rust_helloworld::message::{Message, State};
+use rust_helloworld::notify::{ConsoleNotifier, Dispatcher, Notification, Subscriber};
+use rust_helloworld::storage::MemoryStorage;
+use rust_helloworld::{cli, eat_at_restaurant, mix, PrimaryColor, Rgba};
 
 fn main() {
+    // 带参数运行时作为命令行工具使用，例如 `cargo run -- waitlist add Ann 2`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     println!("Hello, world!");
     // 测试所有权转移和归还
     ownership_test();
@@ -77,6 +92,7 @@ fn ownership_test() {
     println!("s1 = {}", s1);
 }
 
+#[allow(clippy::needless_return)]
 fn takes_ownership_and_give_back(some_string: String) -> String {
     print!("{}", some_string);
     return some_string;
@@ -112,6 +128,8 @@ fn reference_test() {
     // println!("{}, {}, and {}", r1, r2, r3);
 }
 
+// 教程里的写法：显式 return，参数用 &String
+#[allow(clippy::needless_return, clippy::ptr_arg)]
 fn calculate_length(s: &String) -> usize {
     return s.len();
 }
@@ -121,6 +139,7 @@ fn change_string(some_string: &mut String) {
     some_string.push_str(", world");
 }
 
+#[allow(clippy::unnecessary_to_owned)]
 fn string_test() {
     let mut s = String::from("hello");
     s.push(',');
@@ -168,14 +187,9 @@ fn turple_test() {
     println!("{}, {}, {}", i, f, u);
 }
 
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
-
 fn struct_test() {
     let rect1 = Rectangle {
+        origin: Point::new(0, 0),
         width: 30,
         height: 50,
     };
@@ -187,22 +201,29 @@ fn struct_test() {
     dbg!(&rect1);
 }
 
-#[derive(Debug)]
-enum Message {
-    Quit,
-    Move { x: i32, y: i32 },
-    ChangeColor(i32, i32, i32),
-}
-
 fn enum_test() {
     // 枚举类型的不同成员可以是不同的类型
     let m1 = Message::Quit;
     let m2 = Message::Move { x: 1, y: 1 };
-    // todo 获取枚举成员内部的数据
     let m3 = Message::ChangeColor(255, 255, 0);
-    println!("{:?}, {:?}, {:?}", m1, m2, m3)
+    println!("{:?}, {:?}, {:?}", m1, m2, m3);
+
+    // 用模式匹配取出枚举成员内部的数据
+    if let Message::Move { x, y } = m2 {
+        println!("move by ({}, {})", x, y);
+    }
+
+    // 依次执行消息，退出之后的消息会被拒绝
+    let mut state = State::new();
+    for m in [m2, m3, m1, m2] {
+        match state.apply(&m) {
+            Ok(()) => println!("{} -> {:?}", m, state),
+            Err(err) => println!("{} -> {}", m, err),
+        }
+    }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn array_test() {
     // 编译器自动推导出one的类型
     let one = [1, 2, 3];
@@ -232,12 +253,6 @@ fn array_test() {
     }
 }
 
-enum Action {
-    Say(String),
-    MoveTo(i32, i32),
-    ChangeColorRGB(u16, u16, u16),
-}
-
 fn match_test() {
     let actions = [
         Action::Say("Hello Rust".to_string()),
@@ -253,17 +268,22 @@ fn match_test() {
             Action::MoveTo(x, y) => {
                 println!("point from (0, 0) move to ({}, {})", x, y);
             }
-            // Action::ChangeColorRGB(r, g, _) => {
-            //     println!(
-            //         "change color into '(r:{}, g:{}, b:0)', 'b' has been ignored",
-            //         r, g,
-            //     );
-            // }
-            // 通配符，返回单元类型，什么也不发生
-            _ => (),
+            Action::ChangeColorRGB(r, g, b) => {
+                println!("change color into '(r:{}, g:{}, b:{})'", r, g, b);
+            }
+            turtle => println!("turtle action {:?}", turtle),
         }
     }
 
+    // 解释器会检查颜色通道的范围，并且可以撤销
+    let mut interpreter = Interpreter::new();
+    interpreter.apply(Action::MoveTo(1, 2)).unwrap();
+    if let Err(err) = interpreter.apply(Action::ChangeColorRGB(300, 0, 0)) {
+        println!("{}", err);
+    }
+    interpreter.undo();
+    println!("cursor after undo: {:?}", interpreter.cursor());
+
     // 测试匹配值是否存在
     let five = Some(5);
     let six = plus_one(five);
@@ -271,6 +291,8 @@ fn match_test() {
     println!("six: {:?}, none {:?}", six, none)
 }
 
+// 演示 match，不改成 Option::map
+#[allow(clippy::manual_map)]
 fn plus_one(x: Option<i32>) -> Option<i32> {
     match x {
         None => None,
@@ -279,47 +301,16 @@ fn plus_one(x: Option<i32>) -> Option<i32> {
 }
 
 fn method_test() {
-    let rect1 = Rectangle {
-        width: 30,
-        height: 50,
-    };
+    let rect1 = Rectangle::new(30u32, 50);
     println!(
         "The area of the rectangle is {} square pixels.",
         rect1.area()
     );
 
     let m = Message::Quit;
-    m.call();
-    m.call_again()
-}
-
-impl Rectangle {
-    fn area(&self) -> u32 {
-        self.width * self.height
-    }
-}
-
-impl Message {
-    fn call(&self) {
-        // 在这里定义方法体
-    }
-}
-
-// impl 可以定义多个
-impl Message {
-    fn call_again(&self) {}
-}
-
-struct Point<T> {
-    x: T,
-    y: T,
-}
-
-impl<T> Point<T> {
-    // 结构体，方法也可以使用泛型
-    fn x(&self) -> &T {
-        return &self.x;
-    }
+    let mut state = State::new();
+    m.call(&mut state).unwrap();
+    println!("{:?}, running: {}", m.encode(), state.running);
 }
 
 // 测试泛型
@@ -327,86 +318,58 @@ fn generic_test() {
     let p = Point { x: 5, y: 10 };
 
     println!("{}, {}", p.x(), p.y);
-}
 
-pub trait Summary {
-    fn summarize(&self) -> String;
-    fn default_summary(&self) -> String {
-        String::from("(read more ...)")
-    }
-}
-
-pub struct Post {
-    pub title: String,
-    pub author: String,
-    pub content: String,
-}
-
-impl Summary for Post {
-    fn summarize(&self) -> String {
-        format!("文章是{}, 作者是{}", self.title, self.content)
-    }
-}
-
-impl Display for Post {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        return Err(Error);
-    }
+    // Point 实现了 ops::Add、ops::Mul 等运算符特征，可以当作向量来计算
+    let q = p + Point::new(1, 1) * 2;
+    println!("{} -> {}, distance {}", p, q, p.distance(&q));
 }
 
 fn trait_test() {
-    let post = Post {
-        title: "Rust 语言介绍".to_string(),
-        author: "Sunface".to_string(),
-        content: "Rust 棒极了".to_string(),
-    };
+    let mut post = Post::new(1, "Rust 语言介绍", "Sunface", "Rust 棒极了");
+    post.tag("rust");
+    post.publish();
     println!("{}", post.summarize());
     println!("{}", post.default_summary());
-    notify(&post);
-    notify_constrain(&post);
+    // 通知按主题分发给订阅者，这里只有一个打印到终端的订阅者
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(Subscriber::new(ConsoleNotifier::new()).topic("news"));
+    notify(&mut dispatcher, &post);
+    notify_constrain(&mut dispatcher, &post);
+    println!("{}", post);
+    println!("{}", post.render(&Markdown));
+    println!("{}", post.render(&Html));
+
+    let tweet = Tweet::new(2, "rustlang", "Rust 1.0 发布了").unwrap();
+    notify_constrain(&mut dispatcher, &tweet);
+
+    // 检索时中文按字和相邻两字匹配
+    let mut index = SearchIndex::new();
+    index.add(&post);
+    index.add(&tweet);
+    for hit in index.search("发布", 10) {
+        println!("search hit {} score {:.3}", hit.id, hit.score);
+    }
+
+    // Feed 可以放入任何实现了 Summary 的类型
+    let mut feed = Feed::new();
+    feed.push(post);
+    feed.push(tweet);
+    for summary in feed.summaries() {
+        println!("{}", summary);
+    }
     trait_exercise()
 }
 
-fn notify(item: &impl Summary) {
-    println!("Breaking news {}", item.summarize());
+fn notify(dispatcher: &mut Dispatcher, item: &impl Summary) {
+    dispatcher.publish(&Notification::summary("news", item));
 }
 
-fn notify_constrain<T: Summary + Display>(item: &T) {
-    println!("Breaking news {}", item.summarize());
-}
-
-trait Hello {
-    fn say_hi(&self) -> String {
-        String::from("hi")
-    }
-
-    fn say_something(&self) -> String;
-}
-
-struct Student {}
-
-impl Hello for Student {
-    fn say_hi(&self) -> String {
-        return "hi".to_string();
-    }
-
-    fn say_something(&self) -> String {
-        return "I'm a good student".to_string();
-    }
-}
-
-struct Teacher {}
-
-impl Hello for Teacher {
-    fn say_hi(&self) -> String {
-        return "Hi, I'm your new teacher".to_string();
-    }
-
-    fn say_something(&self) -> String {
-        return "I'm not a bad teacher".to_string();
-    }
+fn notify_constrain<T: Summary + Display>(dispatcher: &mut Dispatcher, item: &T) {
+    println!("{}", item);
+    dispatcher.publish(&Notification::summary("news", item));
 }
 
+#[allow(clippy::needless_return)]
 fn multiply<T: Mul<T, Output = T>>(a: T, b: T) -> T {
     return a * b;
 }
@@ -439,13 +402,30 @@ impl ops::Sub<Bar> for Foo {
 }
 
 fn trait_exercise() {
-    let s = Student {};
+    i18n::set_locale(Locale::EnUs);
+    let s = Student::new(1, "sunface", 3);
     assert_eq!(s.say_hi(), "hi");
-    assert_eq!(s.say_something(), "I'm a good student");
-
-    let t = Teacher {};
-    assert_eq!(t.say_hi(), "Hi, I'm your new teacher");
-    assert_eq!(t.say_something(), "I'm not a bad teacher");
+    assert_eq!(s.say_something(), "I'm sunface, a good student in grade 3");
+
+    let t = Teacher::new(2, "Ms. Li", "Rust");
+    assert_eq!(t.say_hi(), "Hi, I'm Ms. Li, your new teacher.");
+    assert_eq!(t.say_something(), "I teach Rust, and I'm not a bad teacher");
+    i18n::set_locale(Locale::from_env());
+
+    // 老师和学生是不同的类型，通过特征对象放在一起
+    let mut roster = Roster::new();
+    roster.add_teacher(t).unwrap();
+    roster.add_student(s).unwrap();
+    let class = roster.add_class("Rust 101", 2).unwrap();
+    roster.enroll(class, 1).unwrap();
+    for line in roster.greet_all(class).unwrap() {
+        println!("{}", line);
+    }
+    let mut book = Gradebook::for_class(&roster, class).unwrap();
+    book.add_category("homework", 1.0).unwrap();
+    let hw = book.add_assignment("hw1", "homework", 10.0).unwrap();
+    book.record(hw, 1, 9.0).unwrap();
+    println!("grade {:?}\n{}", book.letter(1), book.to_csv());
 
     println!("Success!");
 
@@ -493,30 +473,18 @@ fn example1() {
     assert_eq!(cacher.value(15), 11);
 }
 
-trait Draw {
-    fn draw(&self) -> String;
-}
-
-impl Draw for u8 {
-    fn draw(&self) -> String {
-        format!("u8: {}", *self)
-    }
-}
-
-impl Draw for f64 {
-    fn draw(&self) -> String {
-        format!("f64: {}", *self)
-    }
-}
-
 // 若 T 实现了 Draw 特征， 则调用该函数时传入的 Box<T> 可以被隐式转换成函数参数签名中的 Box<dyn Draw>
 fn draw1(x: Box<dyn Draw>) {
     // 由于实现了 Deref 特征，Box 智能指针会自动解引用为它所包裹的值，然后调用该值对应的类型上定义的 `draw` 方法
-    x.draw();
+    let mut buffer = TextBuffer::new(12, 1);
+    x.draw(&DrawContext::new(), &mut buffer);
+    println!("{}", buffer);
 }
 
 fn draw2(x: &dyn Draw) {
-    x.draw();
+    let mut buffer = TextBuffer::new(12, 1);
+    x.draw(&DrawContext::new(), &mut buffer);
+    println!("{}", buffer);
 }
 
 // 测试特征对象
@@ -532,6 +500,16 @@ fn trait_object_test() {
     draw1(Box::new(y));
     draw2(&x);
     draw2(&y);
+
+    // 不同类型的对象放进同一个场景里一起绘制
+    let scene: Vec<Box<dyn Draw>> = vec![
+        Box::new(Rectangle::at(Point::new(1.0, 1.0), 8.0, 4.0)),
+        Box::new(Circle::new(Point::new(16.0, 3.0), 2.5).painted(Rgba::BLACK, Style::Stroke(1.0))),
+        Box::new(x),
+    ];
+    let mut buffer = TextBuffer::new(24, 7);
+    render(&scene, &DrawContext::new(), &mut buffer);
+    println!("{}", buffer);
     trait_object_exec();
 }
 
@@ -550,6 +528,7 @@ impl Duck {
 struct Swan;
 
 impl Swan {
+    #[allow(dead_code)]
     fn fly(&self) {
         println!("Look, the duck.. oh sorry, the swan is flying")
     }
@@ -598,6 +577,7 @@ fn trait_object_exec() {
 }
 
 // 实现以下函数( 返回多个类型）
+#[allow(clippy::needless_return)]
 fn hatch_a_bird(num: i32) -> Box<dyn Bird> {
     if num == 2 {
         return Box::new(Duck {});
@@ -653,6 +633,7 @@ fn vector_test() {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 enum IpAddr {
     V4(String),
     V6(String),
@@ -778,15 +759,20 @@ fn error_test() {
 
 fn read_username_from_file() -> Result<String, io::Error> {
     let mut s = String::new();
-    File::open("hello.txt")?.read_to_string(&mut s);
+    File::open("hello.txt")?.read_to_string(&mut s)?;
     Ok(s)
 }
 
 fn module_test() {
+    i18n::set_locale(Locale::EnUs);
     let secret_number = rand::thread_rng().gen_range(1..100);
     println!("random {}", secret_number);
-    assert_eq!(seat_at_table().as_str(), "sit down please");
+    let mut store = MemoryStorage::new();
+    let party = add_to_waitlist(&mut store, "sunface", 2).unwrap();
+    let seating = seat_at_table(&mut store, party.id, 1).unwrap().unwrap();
+    assert_eq!(seating.greeting().as_str(), "sit down please");
     assert_eq!(eat_at_restaurant().as_str(), "yummy yummy!");
+    i18n::set_locale(Locale::from_env());
 }
 
 fn comment_test() {
@@ -802,11 +788,8 @@ struct Person {
 
 impl Display for Person {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "大佬在上，请受我一拜，小弟姓名{}，年芳{}，家里无田又无车，生活苦哈哈",
-            self.name, self.age
-        )
+        let text = i18n::tr_count("demo.person", self.age as u64, &[("name", &self.name)]);
+        f.write_str(&text)
     }
 }
 
@@ -0,0 +1,283 @@
+//! 消息命令协议：退出、移动、换颜色
+//!
+//! 消息可以从文本命令解析，也可以编码成二进制或者 JSON 在进程间传递，
+//! 最后交给 [`State`] 执行。
+//!
+//! 文本格式每行一条命令：
+//! ```text
+//! quit
+//! move <dx> <dy>        相对当前位置移动
+//! color <r> <g> <b>     每个通道为 0 到 255
+//! ```
+//!
+//! 二进制格式为一个标签字节加上大端序的 `i32` 参数：
+//! `0` 退出，`1` 移动（dx, dy），`2` 换颜色（r, g, b）。
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Point;
+use crate::i18n::tr;
+use crate::kinds::Rgba;
+
+const TAG_QUIT: u8 = 0;
+const TAG_MOVE: u8 = 1;
+const TAG_CHANGE_COLOR: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    ChangeColor(i32, i32, i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageError {
+    /// 文本命令无法解析
+    Parse(String),
+    /// 二进制数据不完整或者标签未知
+    Decode(String),
+    /// 颜色通道超出 0 到 255
+    ChannelOutOfRange(i32),
+    /// 移动后的坐标超出 `i32` 的范围
+    OutOfRange { x: i64, y: i64 },
+    /// 已经收到过退出消息
+    Stopped,
+}
+
+impl Display for MessageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            MessageError::Parse(message) => tr("error.message.parse", &[("message", message)]),
+            MessageError::Decode(message) => tr("error.message.decode", &[("message", message)]),
+            MessageError::ChannelOutOfRange(value) => {
+                tr("error.message.channel", &[("value", value)])
+            }
+            MessageError::OutOfRange { x, y } => {
+                tr("error.message.out_of_range", &[("x", x), ("y", y)])
+            }
+            MessageError::Stopped => tr("error.message.stopped", &[]),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+/// 执行消息的状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct State {
+    pub position: Point<i32>,
+    pub color: Rgba,
+    pub running: bool,
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::new()
+    }
+}
+
+impl State {
+    /// 在原点、黑色、运行中
+    pub fn new() -> State {
+        State {
+            position: Point::origin(),
+            color: Rgba::BLACK,
+            running: true,
+        }
+    }
+
+    /// 执行一条消息，出错时状态保持不变
+    pub fn apply(&mut self, message: &Message) -> Result<(), MessageError> {
+        if !self.running {
+            return Err(MessageError::Stopped);
+        }
+        match *message {
+            Message::Quit => self.running = false,
+            Message::Move { x, y } => {
+                let (px, py) = (self.position.x, self.position.y);
+                match (px.checked_add(x), py.checked_add(y)) {
+                    (Some(x), Some(y)) => self.position = Point::new(x, y),
+                    _ => {
+                        return Err(MessageError::OutOfRange {
+                            x: px as i64 + x as i64,
+                            y: py as i64 + y as i64,
+                        })
+                    }
+                }
+            }
+            Message::ChangeColor(r, g, b) => {
+                self.color = Rgba::rgb(channel(r)?, channel(g)?, channel(b)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// 逐行解析并执行文本命令，空行和 `#` 开头的注释会被跳过
+    /// ```rust
+    /// use rust_helloworld::geometry::Point;
+    /// use rust_helloworld::kinds::Rgba;
+    /// use rust_helloworld::message::{MessageError, State};
+    ///
+    /// let mut state = State::new();
+    /// state.run("move 1 1\n# 黄色\ncolor 255 255 0\nmove 2 -1").unwrap();
+    /// assert_eq!(state.position, Point::new(3, 0));
+    /// assert_eq!(state.color, Rgba::rgb(255, 255, 0));
+    ///
+    /// assert_eq!(state.run("color 300 0 0"), Err(MessageError::ChannelOutOfRange(300)));
+    /// // 坐标溢出时报错，位置保持不变
+    /// assert_eq!(
+    ///     state.run("move 2147483647 0"),
+    ///     Err(MessageError::OutOfRange { x: 2147483650, y: 0 })
+    /// );
+    /// assert_eq!(state.position, Point::new(3, 0));
+    /// state.run("quit").unwrap();
+    /// assert!(!state.running);
+    /// assert_eq!(state.run("move 1 1"), Err(MessageError::Stopped));
+    /// ```
+    pub fn run(&mut self, commands: &str) -> Result<(), MessageError> {
+        for line in commands.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            self.apply(&line.parse()?)?;
+        }
+        Ok(())
+    }
+}
+
+fn channel(value: i32) -> Result<u8, MessageError> {
+    u8::try_from(value).map_err(|_| MessageError::ChannelOutOfRange(value))
+}
+
+impl Message {
+    /// 在 `state` 上执行这条消息
+    pub fn call(&self, state: &mut State) -> Result<(), MessageError> {
+        state.apply(self)
+    }
+}
+
+// impl 可以定义多个，编码相关的方法放在一起
+impl Message {
+    /// 编码为二进制
+    /// ```rust
+    /// use rust_helloworld::message::Message;
+    ///
+    /// let messages = [Message::Move { x: 1, y: -1 }, Message::ChangeColor(255, 255, 0), Message::Quit];
+    /// let mut bytes = Vec::new();
+    /// for message in &messages {
+    ///     bytes.extend(message.encode());
+    /// }
+    /// assert_eq!(&bytes[..9], &[1, 0, 0, 0, 1, 255, 255, 255, 255]);
+    /// assert_eq!(Message::decode_all(&bytes).unwrap(), messages);
+    /// assert!(Message::decode_all(&bytes[..3]).is_err());
+    ///
+    /// let json = Message::ChangeColor(255, 255, 0).to_json();
+    /// assert_eq!(json, r#"{"change_color":[255,255,0]}"#);
+    /// assert_eq!(Message::from_json(&json).unwrap(), Message::ChangeColor(255, 255, 0));
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        let (tag, args): (u8, &[i32]) = match self {
+            Message::Quit => (TAG_QUIT, &[]),
+            Message::Move { x, y } => (TAG_MOVE, &[*x, *y]),
+            Message::ChangeColor(r, g, b) => (TAG_CHANGE_COLOR, &[*r, *g, *b]),
+        };
+        let mut bytes = vec![tag];
+        for arg in args {
+            bytes.extend_from_slice(&arg.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// 从 `bytes` 开头解码一条消息，返回消息和用掉的字节数
+    pub fn decode(bytes: &[u8]) -> Result<(Message, usize), MessageError> {
+        let tag = *bytes
+            .first()
+            .ok_or_else(|| MessageError::Decode(tr("message.empty_input", &[])))?;
+        let arg = |i: usize| -> Result<i32, MessageError> {
+            let start = 1 + i * 4;
+            bytes
+                .get(start..start + 4)
+                .map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| MessageError::Decode(tr("message.truncated", &[("tag", &tag)])))
+        };
+        match tag {
+            TAG_QUIT => Ok((Message::Quit, 1)),
+            TAG_MOVE => Ok((
+                Message::Move {
+                    x: arg(0)?,
+                    y: arg(1)?,
+                },
+                9,
+            )),
+            TAG_CHANGE_COLOR => Ok((Message::ChangeColor(arg(0)?, arg(1)?, arg(2)?), 13)),
+            _ => Err(MessageError::Decode(tr(
+                "message.unknown_tag",
+                &[("tag", &tag)],
+            ))),
+        }
+    }
+
+    /// 解码连续排列的多条消息
+    pub fn decode_all(mut bytes: &[u8]) -> Result<Vec<Message>, MessageError> {
+        let mut messages = Vec::new();
+        while !bytes.is_empty() {
+            let (message, used) = Message::decode(bytes)?;
+            messages.push(message);
+            bytes = &bytes[used..];
+        }
+        Ok(messages)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("message is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Message, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// 输出为文本命令，可以再被解析回来
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::Quit => write!(f, "quit"),
+            Message::Move { x, y } => write!(f, "move {} {}", x, y),
+            Message::ChangeColor(r, g, b) => write!(f, "color {} {} {}", r, g, b),
+        }
+    }
+}
+
+/// ```rust
+/// use rust_helloworld::message::Message;
+///
+/// assert_eq!("move 1 1".parse(), Ok(Message::Move { x: 1, y: 1 }));
+/// assert_eq!("  COLOR 255 255 0 ".parse(), Ok(Message::ChangeColor(255, 255, 0)));
+/// assert!("move 1".parse::<Message>().is_err());
+/// assert_eq!(Message::Move { x: -2, y: 3 }.to_string(), "move -2 3");
+/// ```
+impl FromStr for Message {
+    type Err = MessageError;
+
+    fn from_str(s: &str) -> Result<Message, MessageError> {
+        let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let number = |arg: &str| -> Result<i32, MessageError> {
+            arg.parse()
+                .map_err(|_| MessageError::Parse(tr("message.not_a_number", &[("arg", &arg)])))
+        };
+        match words.as_slice() {
+            ["quit"] => Ok(Message::Quit),
+            ["move", x, y] => Ok(Message::Move {
+                x: number(x)?,
+                y: number(y)?,
+            }),
+            ["color", r, g, b] => Ok(Message::ChangeColor(number(r)?, number(g)?, number(b)?)),
+            _ => Err(MessageError::Parse(s.trim().to_string())),
+        }
+    }
+}
@@ -0,0 +1,529 @@
+//! 通知：把消息按主题分发给订阅者，每个订阅者通过一个 [`Notifier`] 投递
+//!
+//! 内置三种投递方式：打印到终端的 [`ConsoleNotifier`]、追加写入文件的
+//! [`FileNotifier`]，以及 POST 到 HTTP 地址的 [`WebhookNotifier`]。
+//! 订阅者可以限制发送频率，投递失败时按 [`Backoff`] 重试。
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::Summary;
+use crate::i18n::tr;
+use crate::utils::now;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// 主题，用 `.` 分级，比如 `news.rust`
+    pub topic: String,
+    pub message: String,
+    /// 创建时间（unix 秒）
+    pub at: u64,
+}
+
+impl Notification {
+    pub fn new(topic: &str, message: &str) -> Notification {
+        Notification {
+            topic: topic.to_string(),
+            message: message.to_string(),
+            at: now(),
+        }
+    }
+
+    /// 用内容的摘要作为消息，时间取发布时间
+    pub fn summary(topic: &str, item: &dyn Summary) -> Notification {
+        Notification {
+            topic: topic.to_string(),
+            message: item.summarize(),
+            at: item.published_at().unwrap_or_else(now),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Io(io::Error),
+    /// webhook 返回了非 2xx 的状态码
+    Status(u16),
+    InvalidUrl(String),
+}
+
+impl NotifyError {
+    /// 网络错误、5xx 和 429 可能是暂时的，值得重试
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            NotifyError::Io(_) => true,
+            NotifyError::Status(code) => *code >= 500 || *code == 429,
+            NotifyError::InvalidUrl(_) => false,
+        }
+    }
+}
+
+impl Display for NotifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            NotifyError::Io(err) => tr("error.notify.io", &[("error", err)]),
+            NotifyError::Status(code) => tr("error.notify.status", &[("code", code)]),
+            NotifyError::InvalidUrl(url) => tr("error.notify.invalid_url", &[("url", url)]),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for NotifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NotifyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NotifyError {
+    fn from(err: io::Error) -> NotifyError {
+        NotifyError::Io(err)
+    }
+}
+
+/// 投递通知的方式
+pub trait Notifier {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// 每条通知写一行 `[topic] message`，默认写到标准输出
+/// ```rust
+/// use rust_helloworld::notify::{ConsoleNotifier, Notification, Notifier};
+///
+/// let mut console = ConsoleNotifier::with_writer(Vec::new());
+/// console.notify(&Notification::new("news", "Rust 1.0 发布了")).unwrap();
+/// assert_eq!(console.into_inner(), "[news] Rust 1.0 发布了\n".as_bytes());
+/// ```
+pub struct ConsoleNotifier<W: Write = io::Stdout> {
+    writer: W,
+}
+
+impl ConsoleNotifier {
+    pub fn new() -> ConsoleNotifier {
+        ConsoleNotifier {
+            writer: io::stdout(),
+        }
+    }
+}
+
+impl Default for ConsoleNotifier {
+    fn default() -> ConsoleNotifier {
+        ConsoleNotifier::new()
+    }
+}
+
+impl<W: Write> ConsoleNotifier<W> {
+    pub fn with_writer(writer: W) -> ConsoleNotifier<W> {
+        ConsoleNotifier { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Notifier for ConsoleNotifier<W> {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError> {
+        writeln!(
+            self.writer,
+            "[{}] {}",
+            notification.topic, notification.message
+        )?;
+        Ok(())
+    }
+}
+
+/// 把通知以 JSON Lines 格式追加到文件末尾，文件不存在时创建
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileNotifier {
+        FileNotifier { path: path.into() }
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut line = serde_json::to_string(notification).map_err(io::Error::other)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // 一次写完整行，多个进程同时追加时不会交错
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// 把通知以 JSON 格式 POST 到 `http://host[:port]/path`，2xx 以外的状态码视为失败
+///
+/// IPv6 地址要写在方括号里，比如 `http://[::1]:8080/hook`。
+pub struct WebhookNotifier {
+    host: String,
+    port: u16,
+    path: String,
+    timeout: Duration,
+}
+
+impl WebhookNotifier {
+    /// 只支持 http
+    pub fn new(url: &str) -> Result<WebhookNotifier, NotifyError> {
+        let invalid = || NotifyError::InvalidUrl(url.to_string());
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.strip_prefix('[') {
+            // [::1] 或者 [::1]:8080，地址中的冒号不是端口的分隔符
+            Some(bracketed) => {
+                let (host, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+                match rest.strip_prefix(':') {
+                    Some(port) => (host, port.parse().map_err(|_| invalid())?),
+                    None if rest.is_empty() => (host, 80),
+                    None => return Err(invalid()),
+                }
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+                None => (authority, 80),
+            },
+        };
+        // 没有方括号的 IPv6 地址分不清端口
+        if host.is_empty() || (host.contains(':') && !authority.starts_with('[')) {
+            return Err(invalid());
+        }
+        Ok(WebhookNotifier {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            timeout: Duration::from_secs(5),
+        })
+    }
+
+    /// 连接、发送和等待响应各自的超时时间，默认 5 秒
+    pub fn timeout(mut self, timeout: Duration) -> WebhookNotifier {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&mut self, notification: &Notification) -> Result<(), NotifyError> {
+        let body = serde_json::to_string(notification).map_err(io::Error::other)?;
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| NotifyError::InvalidUrl(self.host.clone()))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        // Host 头中的 IPv6 地址同样要加方括号
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            host,
+            self.port,
+            body.len(),
+            body
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        // 状态行：HTTP/1.1 200 OK
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed response"))?;
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(NotifyError::Status(status))
+        }
+    }
+}
+
+/// 滑动窗口限流：任意 `per` 时间内最多放行 `max` 次
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use rust_helloworld::notify::RateLimiter;
+///
+/// let mut limiter = RateLimiter::new(2, Duration::from_secs(60));
+/// let start = Instant::now();
+/// assert!(limiter.try_acquire(start));
+/// assert!(limiter.try_acquire(start + Duration::from_secs(1)));
+/// assert!(!limiter.try_acquire(start + Duration::from_secs(2)));
+/// // 第一次放行已经滑出窗口
+/// assert!(limiter.try_acquire(start + Duration::from_secs(60)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    max: usize,
+    per: Duration,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, per: Duration) -> RateLimiter {
+        RateLimiter {
+            max,
+            per,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// 放行时记下这一次，超出限制时返回 false
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        while let Some(&first) = self.sent.front() {
+            if now.duration_since(first) < self.per {
+                break;
+            }
+            self.sent.pop_front();
+        }
+        if self.sent.len() < self.max {
+            self.sent.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 指数退避：第 n 次重试前等待 `initial * 2^(n-1)`，不超过 `max`
+/// ```rust
+/// use std::time::Duration;
+/// use rust_helloworld::notify::Backoff;
+///
+/// let backoff = Backoff::new(4, Duration::from_millis(100)).max_delay(Duration::from_millis(300));
+/// let delays: Vec<u128> = backoff.delays().map(|d| d.as_millis()).collect();
+/// assert_eq!(delays, vec![100, 200, 300, 300]);
+/// assert_eq!(Backoff::none().delays().count(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub retries: u32,
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Backoff {
+    pub fn new(retries: u32, initial: Duration) -> Backoff {
+        Backoff {
+            retries,
+            initial,
+            max: Duration::from_secs(30),
+        }
+    }
+
+    /// 失败后不重试
+    pub fn none() -> Backoff {
+        Backoff::new(0, Duration::ZERO)
+    }
+
+    pub fn max_delay(mut self, max: Duration) -> Backoff {
+        self.max = max;
+        self
+    }
+
+    /// 每次重试前等待的时间
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        (0..self.retries).map(move |n| {
+            self.initial
+                .checked_mul(2u32.saturating_pow(n))
+                .map_or(self.max, |d| d.min(self.max))
+        })
+    }
+}
+
+/// 订阅者，不指定主题时接收所有通知
+pub struct Subscriber {
+    notifier: Box<dyn Notifier>,
+    topics: Vec<String>,
+    limiter: Option<RateLimiter>,
+    backoff: Backoff,
+}
+
+impl Subscriber {
+    pub fn new<N: Notifier + 'static>(notifier: N) -> Subscriber {
+        Subscriber {
+            notifier: Box::new(notifier),
+            topics: Vec::new(),
+            limiter: None,
+            backoff: Backoff::none(),
+        }
+    }
+
+    /// 订阅主题，`news` 只匹配 `news`，`news.*` 匹配 `news` 下的所有子主题，`*` 匹配所有主题
+    pub fn topic(mut self, pattern: &str) -> Subscriber {
+        self.topics.push(pattern.to_string());
+        self
+    }
+
+    pub fn rate_limit(mut self, max: usize, per: Duration) -> Subscriber {
+        self.limiter = Some(RateLimiter::new(max, per));
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Subscriber {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn accepts(&self, topic: &str) -> bool {
+        self.topics.is_empty() || self.topics.iter().any(|p| topic_matches(p, topic))
+    }
+}
+
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => pattern == topic,
+    }
+}
+
+/// 一个订阅者的投递结果
+#[derive(Debug)]
+pub enum Delivery {
+    Delivered {
+        attempts: u32,
+    },
+    /// 超出频率限制，这条通知被丢弃
+    RateLimited,
+    Failed {
+        attempts: u32,
+        error: NotifyError,
+    },
+}
+
+impl Delivery {
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, Delivery::Delivered { .. })
+    }
+}
+
+/// 按主题把通知分发给订阅者
+/// ```rust
+/// use std::time::Duration;
+/// use rust_helloworld::notify::{
+///     Backoff, Delivery, Dispatcher, Notification, Notifier, NotifyError, Subscriber,
+/// };
+///
+/// // 前两次失败的投递方式
+/// struct Flaky(u32);
+/// impl Notifier for Flaky {
+///     fn notify(&mut self, _: &Notification) -> Result<(), NotifyError> {
+///         self.0 += 1;
+///         if self.0 <= 2 { Err(NotifyError::Status(503)) } else { Ok(()) }
+///     }
+/// }
+///
+/// let mut dispatcher = Dispatcher::new();
+/// let flaky = dispatcher.subscribe(
+///     Subscriber::new(Flaky(0))
+///         .topic("news.*")
+///         .backoff(Backoff::new(3, Duration::from_millis(1))),
+/// );
+/// let limited = dispatcher.subscribe(Subscriber::new(Flaky(2)).rate_limit(1, Duration::from_secs(60)));
+///
+/// let results = dispatcher.publish(&Notification::new("news.rust", "Rust 1.0 发布了"));
+/// assert!(matches!(results[0], (id, Delivery::Delivered { attempts: 3 }) if id == flaky));
+/// assert!(matches!(results[1], (id, Delivery::Delivered { attempts: 1 }) if id == limited));
+///
+/// // 主题不匹配的订阅者收不到，超出频率的通知被丢弃
+/// let results = dispatcher.publish(&Notification::new("weather", "晴"));
+/// assert_eq!(results.len(), 1);
+/// assert!(matches!(results[0].1, Delivery::RateLimited));
+/// ```
+#[derive(Default)]
+pub struct Dispatcher {
+    subscribers: Vec<(u64, Subscriber)>,
+    next_id: u64,
+}
+
+impl Dispatcher {
+    pub fn new() -> Dispatcher {
+        Dispatcher::default()
+    }
+
+    /// 返回订阅 id，用于取消订阅
+    pub fn subscribe(&mut self, subscriber: Subscriber) -> u64 {
+        self.next_id += 1;
+        self.subscribers.push((self.next_id, subscriber));
+        self.next_id
+    }
+
+    pub fn unsubscribe(&mut self, id: u64) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers.retain(|(i, _)| *i != id);
+        self.subscribers.len() != len
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// 按订阅顺序依次投递，返回每个匹配的订阅者的投递结果
+    ///
+    /// 重试会阻塞当前线程，一个订阅者失败不影响其他订阅者。
+    pub fn publish(&mut self, notification: &Notification) -> Vec<(u64, Delivery)> {
+        let mut results = Vec::new();
+        for (id, subscriber) in &mut self.subscribers {
+            if !subscriber.accepts(&notification.topic) {
+                continue;
+            }
+            if let Some(limiter) = &mut subscriber.limiter {
+                if !limiter.try_acquire(Instant::now()) {
+                    results.push((*id, Delivery::RateLimited));
+                    continue;
+                }
+            }
+            results.push((*id, deliver(subscriber, notification)));
+        }
+        results
+    }
+}
+
+fn deliver(subscriber: &mut Subscriber, notification: &Notification) -> Delivery {
+    let mut delays = subscriber.backoff.delays();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match subscriber.notifier.notify(notification) {
+            Ok(()) => return Delivery::Delivered { attempts },
+            Err(error) => error,
+        };
+        match delays.next() {
+            Some(delay) if error.is_retryable() => thread::sleep(delay),
+            _ => return Delivery::Failed { attempts, error },
+        }
+    }
+}
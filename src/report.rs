@@ -0,0 +1,213 @@
+//! 营业报表：日结 Z 报表，以及按类别、菜品、小时统计的销售额
+//!
+//! 一天按 UTC 划分，订单按下单时间归到某一天，只有已付款的订单计入销售额；
+//! 作废的订单单独统计。小费跟着订单走，跨过 0 点才付款的订单，小费也算在下单那一天。
+//! 金额单位都是分。
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::csv;
+use crate::front_of_house::serving::{Order, OrderStatus, Payment};
+use crate::storage::{Storage, StorageError};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// 一行销售统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SalesLine {
+    pub key: String,
+    pub quantity: u32,
+    pub amount: u64,
+}
+
+/// 作废或者招待的次数与金额
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Adjustment {
+    pub count: u32,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZReport {
+    /// 当天 0 点（UTC）的 unix 时间戳
+    pub day: u64,
+    /// 已付款的订单数
+    pub checks: u32,
+    /// 就餐人数
+    pub covers: u32,
+    pub gross_sales: u64,
+    pub tips: u64,
+    /// 平均每单金额
+    pub average_check: u64,
+    /// 人均消费
+    pub average_per_cover: u64,
+    pub voids: Adjustment,
+    pub comps: Adjustment,
+    pub by_category: Vec<SalesLine>,
+    pub by_item: Vec<SalesLine>,
+    /// 按下单的小时（UTC）统计
+    pub by_hour: Vec<SalesLine>,
+}
+
+fn add_line(lines: &mut BTreeMap<String, (u32, u64)>, key: String, quantity: u32, amount: u64) {
+    let line = lines.entry(key).or_insert((0, 0));
+    line.0 += quantity;
+    line.1 += amount;
+}
+
+fn into_lines(lines: BTreeMap<String, (u32, u64)>) -> Vec<SalesLine> {
+    lines
+        .into_iter()
+        .map(|(key, (quantity, amount))| SalesLine {
+            key,
+            quantity,
+            amount,
+        })
+        .collect()
+}
+
+impl ZReport {
+    /// 统计 `time` 所在那一天的订单和付款
+    /// ```rust
+    /// use rust_helloworld::front_of_house::serving::{Order, OrderItem, OrderStatus, Payment};
+    /// use rust_helloworld::report::ZReport;
+    ///
+    /// let order = Order {
+    ///     id: 1,
+    ///     table: 2,
+    ///     covers: 2,
+    ///     items: vec![
+    ///         OrderItem::new("饺子", "主食", 1200, 2),
+    ///         OrderItem::new("茶", "饮品", 300, 1).comp(),
+    ///     ],
+    ///     status: OrderStatus::Paid,
+    ///     opened_at: 3600 * 19,
+    /// };
+    /// let payment = Payment { id: 2, order_id: 1, amount: 2400, tip: 200, paid_at: 3600 * 20 };
+    ///
+    /// let report = ZReport::build(&[order], &[payment], 3600 * 12);
+    /// assert_eq!(report.gross_sales, 2400);
+    /// assert_eq!(report.average_per_cover, 1200);
+    /// assert_eq!(report.comps.amount, 300);
+    /// assert_eq!(report.by_hour[0].key, "19");
+    /// assert!(report.to_csv().contains("category,主食,2,2400"));
+    /// ```
+    pub fn build(orders: &[Order], payments: &[Payment], time: u64) -> ZReport {
+        let day = time - time % SECONDS_PER_DAY;
+        let in_day = |t: u64| t >= day && t < day + SECONDS_PER_DAY;
+
+        let mut report = ZReport {
+            day,
+            checks: 0,
+            covers: 0,
+            gross_sales: 0,
+            tips: 0,
+            average_check: 0,
+            average_per_cover: 0,
+            voids: Adjustment::default(),
+            comps: Adjustment::default(),
+            by_category: Vec::new(),
+            by_item: Vec::new(),
+            by_hour: Vec::new(),
+        };
+        let mut by_category = BTreeMap::new();
+        let mut by_item = BTreeMap::new();
+        let mut by_hour = BTreeMap::new();
+        let mut paid_orders = BTreeSet::new();
+
+        for order in orders.iter().filter(|order| in_day(order.opened_at)) {
+            match order.status {
+                OrderStatus::Voided => {
+                    report.voids.count += 1;
+                    report.voids.amount += order.total();
+                    continue;
+                }
+                OrderStatus::Paid => {}
+                _ => continue,
+            }
+            paid_orders.insert(order.id);
+            report.checks += 1;
+            report.covers += order.covers;
+            report.gross_sales += order.total();
+
+            let hour = format!("{:02}", order.opened_at % SECONDS_PER_DAY / 3600);
+            add_line(&mut by_hour, hour, 1, order.total());
+            for item in &order.items {
+                if item.comped {
+                    report.comps.count += item.quantity;
+                    report.comps.amount += item.list_total();
+                    continue;
+                }
+                add_line(
+                    &mut by_category,
+                    item.category.clone(),
+                    item.quantity,
+                    item.total(),
+                );
+                add_line(&mut by_item, item.name.clone(), item.quantity, item.total());
+            }
+        }
+
+        report.tips = payments
+            .iter()
+            .filter(|payment| paid_orders.contains(&payment.order_id))
+            .map(|payment| payment.tip)
+            .sum();
+        if report.checks > 0 {
+            report.average_check = report.gross_sales / report.checks as u64;
+        }
+        if report.covers > 0 {
+            report.average_per_cover = report.gross_sales / report.covers as u64;
+        }
+        report.by_category = into_lines(by_category);
+        report.by_item = into_lines(by_item);
+        report.by_hour = into_lines(by_hour);
+        report
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is always serializable")
+    }
+
+    /// 导出为 CSV，每行是 `section,key,quantity,amount`，没有意义的列留空
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,key,quantity,amount\n");
+        let mut row = |section: &str, key: &str, quantity: Option<u32>, amount: Option<u64>| {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                section,
+                csv::field(key),
+                quantity.map(|n| n.to_string()).unwrap_or_default(),
+                amount.map(|n| n.to_string()).unwrap_or_default()
+            ));
+        };
+        let summary = [
+            ("gross_sales", Some(self.checks), Some(self.gross_sales)),
+            ("covers", Some(self.covers), None),
+            ("average_check", None, Some(self.average_check)),
+            ("average_per_cover", None, Some(self.average_per_cover)),
+            ("tips", Some(self.checks), Some(self.tips)),
+            ("voids", Some(self.voids.count), Some(self.voids.amount)),
+            ("comps", Some(self.comps.count), Some(self.comps.amount)),
+        ];
+        for (key, quantity, amount) in summary {
+            row("summary", key, quantity, amount);
+        }
+        for (section, lines) in [
+            ("category", &self.by_category),
+            ("item", &self.by_item),
+            ("hour", &self.by_hour),
+        ] {
+            for line in lines {
+                row(section, &line.key, Some(line.quantity), Some(line.amount));
+            }
+        }
+        csv
+    }
+}
+
+/// 从存储中读取订单和付款，生成 `time` 所在那一天的 Z 报表
+pub fn z_report(store: &dyn Storage, time: u64) -> Result<ZReport, StorageError> {
+    Ok(ZReport::build(&store.orders()?, &store.payments()?, time))
+}
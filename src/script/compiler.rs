@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use super::parser::{Expr, ExprKind, Op, Stmt, StmtKind};
+use super::{Pos, ScriptError};
+use crate::action::Action;
+use crate::i18n::tr;
+
+/// 最多生成的动作数
+const MAX_ACTIONS: usize = 100_000;
+/// 最多执行的步数（语句和循环次数），防止空循环跑很久
+const MAX_STEPS: usize = 1_000_000;
+/// 字符串的最大长度（字节），防止反复拼接耗尽内存
+const MAX_STRING_LEN: usize = 64 * 1024;
+/// `say` 输出的总字节数上限，防止循环里反复输出长字符串耗尽内存
+const MAX_OUTPUT: usize = 1024 * 1024;
+/// 过程调用的最大深度
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Str(String),
+}
+
+impl Value {
+    fn text(&self) -> String {
+        match self {
+            Value::Int(n) => n.to_string(),
+            Value::Str(s) => s.clone(),
+        }
+    }
+}
+
+struct Procedure<'a> {
+    params: &'a [String],
+    body: &'a [Stmt],
+}
+
+struct Compiler<'a> {
+    procs: HashMap<&'a str, Procedure<'a>>,
+    globals: HashMap<String, Value>,
+    /// 过程调用的局部变量，栈顶是当前的过程
+    frames: Vec<HashMap<String, Value>>,
+    actions: Vec<(Action, Pos)>,
+    steps: usize,
+    /// 已经输出的字节数
+    output: usize,
+}
+
+pub(crate) fn compile(program: &[Stmt]) -> Result<Vec<(Action, Pos)>, ScriptError> {
+    let mut compiler = Compiler {
+        procs: HashMap::new(),
+        globals: HashMap::new(),
+        frames: Vec::new(),
+        actions: Vec::new(),
+        steps: 0,
+        output: 0,
+    };
+    // 过程可以在定义之前调用
+    for stmt in program {
+        if let StmtKind::Proc(name, params, body) = &stmt.kind {
+            if compiler.procs.contains_key(name.as_str()) {
+                return Err(ScriptError::new(
+                    stmt.pos,
+                    tr("script.proc_defined", &[("name", name)]),
+                ));
+            }
+            compiler.procs.insert(name, Procedure { params, body });
+        }
+    }
+    for stmt in program {
+        if !matches!(stmt.kind, StmtKind::Proc(..)) {
+            compiler.stmt(stmt)?;
+        }
+    }
+    Ok(compiler.actions)
+}
+
+fn int(value: Value, pos: Pos) -> Result<i64, ScriptError> {
+    match value {
+        Value::Int(n) => Ok(n),
+        Value::Str(s) => Err(ScriptError::new(
+            pos,
+            tr("script.expected_number", &[("found", &s)]),
+        )),
+    }
+}
+
+fn overflow(pos: Pos) -> ScriptError {
+    ScriptError::new(pos, tr("script.overflow", &[]))
+}
+
+impl<'a> Compiler<'a> {
+    fn scope(&mut self) -> &mut HashMap<String, Value> {
+        match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => &mut self.globals,
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.frames
+            .last()
+            .and_then(|frame| frame.get(name))
+            .or_else(|| self.globals.get(name))
+    }
+
+    fn emit(&mut self, action: Action, pos: Pos) -> Result<(), ScriptError> {
+        if self.actions.len() >= MAX_ACTIONS {
+            return Err(ScriptError::new(
+                pos,
+                tr("script.too_many_actions", &[("max", &MAX_ACTIONS)]),
+            ));
+        }
+        self.actions.push((action, pos));
+        Ok(())
+    }
+
+    fn tick(&mut self, pos: Pos) -> Result<(), ScriptError> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(ScriptError::new(
+                pos,
+                tr("script.too_many_steps", &[("max", &MAX_STEPS)]),
+            ));
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, stmts: &'a [Stmt]) -> Result<(), ScriptError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) -> Result<(), ScriptError> {
+        let pos = stmt.pos;
+        self.tick(pos)?;
+        match &stmt.kind {
+            StmtKind::Let(name, value) => {
+                let value = self.eval(value)?;
+                self.scope().insert(name.clone(), value);
+            }
+            StmtKind::Assign(name, value) => {
+                let value = self.eval(value)?;
+                let slot = match self.frames.last_mut() {
+                    Some(frame) if frame.contains_key(name) => frame.get_mut(name),
+                    _ => self.globals.get_mut(name),
+                };
+                match slot {
+                    Some(slot) => *slot = value,
+                    None => {
+                        return Err(ScriptError::new(
+                            pos,
+                            tr("script.assign_undefined", &[("name", name)]),
+                        ))
+                    }
+                }
+            }
+            StmtKind::Say(value) => {
+                let text = self.eval(value)?.text();
+                self.output += text.len();
+                if self.output > MAX_OUTPUT {
+                    return Err(ScriptError::new(
+                        pos,
+                        tr("script.too_much_output", &[("max", &MAX_OUTPUT)]),
+                    ));
+                }
+                self.emit(Action::Say(text), pos)?;
+            }
+            StmtKind::Move(x, y) => {
+                let x = self.coordinate(x)?;
+                let y = self.coordinate(y)?;
+                self.emit(Action::MoveTo(x, y), pos)?;
+            }
+            StmtKind::Color(r, g, b) => {
+                let (r, g, b) = (self.channel(r)?, self.channel(g)?, self.channel(b)?);
+                self.emit(Action::ChangeColorRGB(r, g, b), pos)?;
+            }
+            StmtKind::Forward(distance) => {
+                let distance = self.int32(distance, "script.distance")?;
+                self.emit(Action::Forward(distance), pos)?;
+            }
+            StmtKind::Turn(degrees) => {
+                let degrees = self.int32(degrees, "script.angle")?;
+                self.emit(Action::Turn(degrees), pos)?;
+            }
+            StmtKind::Pen(true) => self.emit(Action::PenDown, pos)?,
+            StmtKind::Pen(false) => self.emit(Action::PenUp, pos)?,
+            StmtKind::Repeat(count, body) => {
+                let count = int(self.eval(count)?, count.pos)?;
+                for _ in 0..count {
+                    self.tick(pos)?;
+                    self.block(body)?;
+                }
+            }
+            StmtKind::For(var, from, to, body) => {
+                let from = int(self.eval(from)?, from.pos)?;
+                let to = int(self.eval(to)?, to.pos)?;
+                for i in from..to {
+                    self.tick(pos)?;
+                    self.scope().insert(var.clone(), Value::Int(i));
+                    self.block(body)?;
+                }
+            }
+            StmtKind::Proc(name, _, _) => {
+                return Err(ScriptError::new(
+                    pos,
+                    tr("script.proc_not_top_level", &[("name", name)]),
+                ))
+            }
+            StmtKind::Call(name, args) => self.call(name, args, pos)?,
+        }
+        Ok(())
+    }
+
+    fn call(&mut self, name: &str, args: &'a [Expr], pos: Pos) -> Result<(), ScriptError> {
+        let (params, body) = match self.procs.get(name) {
+            Some(proc) => (proc.params, proc.body),
+            None => {
+                return Err(ScriptError::new(
+                    pos,
+                    tr("script.undefined_proc", &[("name", &name)]),
+                ))
+            }
+        };
+        if params.len() != args.len() {
+            return Err(ScriptError::new(
+                pos,
+                tr(
+                    "script.arity",
+                    &[
+                        ("name", &name),
+                        ("expected", &params.len()),
+                        ("given", &args.len()),
+                    ],
+                ),
+            ));
+        }
+        if self.frames.len() >= MAX_DEPTH {
+            return Err(ScriptError::new(
+                pos,
+                tr("script.too_deep", &[("max", &MAX_DEPTH)]),
+            ));
+        }
+        let mut frame = HashMap::new();
+        for (param, arg) in params.iter().zip(args) {
+            frame.insert(param.clone(), self.eval(arg)?);
+        }
+        self.frames.push(frame);
+        let result = self.block(body);
+        self.frames.pop();
+        result
+    }
+
+    fn coordinate(&self, expr: &Expr) -> Result<i32, ScriptError> {
+        self.int32(expr, "script.coordinate")
+    }
+
+    /// 求值并检查能否放进 `i32`，`what` 是错误信息中数值名称的消息键
+    fn int32(&self, expr: &Expr, what: &str) -> Result<i32, ScriptError> {
+        let n = int(self.eval(expr)?, expr.pos)?;
+        i32::try_from(n).map_err(|_| {
+            let what = tr(what, &[]);
+            ScriptError::new(
+                expr.pos,
+                tr("script.out_of_range", &[("what", &what), ("value", &n)]),
+            )
+        })
+    }
+
+    /// 只检查能否放进 `u16`，是否超出 0 到 255 交给解释器检查
+    fn channel(&self, expr: &Expr) -> Result<u16, ScriptError> {
+        let n = int(self.eval(expr)?, expr.pos)?;
+        u16::try_from(n).map_err(|_| {
+            ScriptError::new(
+                expr.pos,
+                tr("script.channel_out_of_range", &[("value", &n)]),
+            )
+        })
+    }
+
+    fn eval(&self, expr: &Expr) -> Result<Value, ScriptError> {
+        let pos = expr.pos;
+        match &expr.kind {
+            ExprKind::Int(n) => Ok(Value::Int(*n)),
+            ExprKind::Str(s) => Ok(Value::Str(s.clone())),
+            ExprKind::Var(name) => self.lookup(name).cloned().ok_or_else(|| {
+                ScriptError::new(pos, tr("script.undefined_variable", &[("name", name)]))
+            }),
+            ExprKind::Neg(value) => {
+                let n = int(self.eval(value)?, value.pos)?;
+                n.checked_neg().map(Value::Int).ok_or_else(|| overflow(pos))
+            }
+            ExprKind::Binary(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                // 有一边是字符串时，`+` 为拼接
+                if let (Op::Add, Value::Str(_), _) | (Op::Add, _, Value::Str(_)) =
+                    (op, &left, &right)
+                {
+                    let (left, right) = (left.text(), right.text());
+                    if left.len() + right.len() > MAX_STRING_LEN {
+                        return Err(ScriptError::new(
+                            pos,
+                            tr("script.string_too_long", &[("max", &MAX_STRING_LEN)]),
+                        ));
+                    }
+                    return Ok(Value::Str(left + &right));
+                }
+                let (a, b) = (int(left, pos)?, int(right, pos)?);
+                if b == 0 && matches!(op, Op::Div | Op::Rem) {
+                    return Err(ScriptError::new(pos, tr("script.division_by_zero", &[])));
+                }
+                let result = match op {
+                    Op::Add => a.checked_add(b),
+                    Op::Sub => a.checked_sub(b),
+                    Op::Mul => a.checked_mul(b),
+                    Op::Div => a.checked_div(b),
+                    Op::Rem => a.checked_rem(b),
+                };
+                result.map(Value::Int).ok_or_else(|| overflow(pos))
+            }
+        }
+    }
+}
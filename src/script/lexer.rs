@@ -0,0 +1,113 @@
+use super::{Pos, ScriptError};
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Ident(String),
+    Number(i64),
+    Str(String),
+    /// 单个或两个字符的符号，比如 `{`、`..`
+    Symbol(&'static str),
+    /// 换行或者分号，用来分隔语句
+    End,
+}
+
+const SYMBOLS: [&str; 12] = ["..", "(", ")", "{", "}", ",", "=", "+", "-", "*", "/", "%"];
+
+pub(crate) fn tokenize(source: &str) -> Result<Vec<(Token, Pos)>, ScriptError> {
+    let mut tokens = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let pos = Pos {
+                line: index + 1,
+                column: i + 1,
+            };
+            if c == '#' {
+                break;
+            } else if c.is_whitespace() {
+                i += 1;
+            } else if c == ';' {
+                tokens.push((Token::End, pos));
+                i += 1;
+            } else if c.is_ascii_digit() {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text.parse().map_err(|_| {
+                    ScriptError::new(pos, tr("script.number_too_large", &[("text", &text)]))
+                })?;
+                tokens.push((Token::Number(number), pos));
+            } else if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push((Token::Ident(chars[start..i].iter().collect()), pos));
+            } else if c == '"' {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(ScriptError::new(
+                                pos,
+                                tr("script.unterminated_string", &[]),
+                            ))
+                        }
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('n') => text.push('\n'),
+                                Some(&c @ ('"' | '\\')) => text.push(c),
+                                _ => {
+                                    let pos = Pos {
+                                        column: i + 1,
+                                        ..pos
+                                    };
+                                    return Err(ScriptError::new(
+                                        pos,
+                                        tr("script.invalid_escape", &[]),
+                                    ));
+                                }
+                            }
+                            i += 2;
+                        }
+                        Some(&c) => {
+                            text.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                tokens.push((Token::Str(text), pos));
+            } else {
+                let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                match SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+                    Some(symbol) => {
+                        tokens.push((Token::Symbol(symbol), pos));
+                        i += symbol.len();
+                    }
+                    None => {
+                        return Err(ScriptError::new(
+                            pos,
+                            tr("script.unexpected_character", &[("char", &c)]),
+                        ))
+                    }
+                }
+            }
+        }
+        tokens.push((
+            Token::End,
+            Pos {
+                line: index + 1,
+                column: chars.len() + 1,
+            },
+        ));
+    }
+    Ok(tokens)
+}
@@ -0,0 +1,142 @@
+//! 给设计师用的动作脚本，编译成一串 [`Action`]，再交给 [`Interpreter`] 或者其他
+//! [`ActionTarget`] 执行
+//!
+//! ```text
+//! # 注释从 # 开始到行尾，语句之间用换行或者分号分隔
+//! let size = 10                 定义变量，值为整数或者字符串
+//! size = size * 2               给已有的变量赋值
+//! say "size is " + size         说话，`+` 的一边是字符串时为拼接
+//! move size, 0                  移动到绝对坐标
+//! color 255, 200, 0             换颜色
+//! forward 10                    沿朝向前进，负数为后退
+//! turn 90                       右转，单位为度，负数为左转
+//! pen up / pen down             抬笔 / 落笔
+//! repeat 4 { ... }              重复执行
+//! for i in 0..size { ... }      i 从 0 到 size - 1
+//! proc square(x, y) { ... }     定义过程，只能写在最外层，可以先调用后定义
+//! square(1, 2)                  调用过程
+//! ```
+//!
+//! 整数支持 `+ - * / %`、取负和括号。过程里的 `let` 定义局部变量，
+//! 找不到局部变量时使用最外层的变量。括号、取负、运算符和代码块最多嵌套 128 层，
+//! `say` 的输出总共不超过 1 MiB。
+use std::fmt::{Display, Formatter};
+
+use crate::action::{Action, ActionTarget};
+use crate::i18n::tr;
+
+mod compiler;
+mod lexer;
+mod parser;
+
+/// 脚本中的位置，行和列都从 1 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// 脚本的语法错误或者执行错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub pos: Pos,
+    pub message: String,
+}
+
+impl ScriptError {
+    pub(crate) fn new<S: Into<String>>(pos: Pos, message: S) -> ScriptError {
+        ScriptError {
+            pos,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = tr(
+            "error.script",
+            &[
+                ("line", &self.pos.line),
+                ("column", &self.pos.column),
+                ("message", &self.message),
+            ],
+        );
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn compile_with_pos(source: &str) -> Result<Vec<(Action, Pos)>, ScriptError> {
+    let program = parser::parse(lexer::tokenize(source)?)?;
+    compiler::compile(&program)
+}
+
+/// 把脚本展开成动作序列
+/// ```rust
+/// use rust_helloworld::action::Action;
+/// use rust_helloworld::i18n::{set_locale, Locale};
+/// use rust_helloworld::script::compile;
+///
+/// set_locale(Locale::EnUs);
+/// let actions = compile(r#"
+///     proc step(n) {
+///         move n * 10, -n
+///     }
+///     let name = "Ann"
+///     for i in 1..3 { step(i) }
+///     say "hi " + name; color 255, 0, 0
+/// "#).unwrap();
+/// assert_eq!(actions, vec![
+///     Action::MoveTo(10, -1),
+///     Action::MoveTo(20, -2),
+///     Action::Say("hi Ann".to_string()),
+///     Action::ChangeColorRGB(255, 0, 0),
+/// ]);
+///
+/// let err = compile("repeat 2 {\n  move 1, x\n}").unwrap_err();
+/// assert_eq!(err.to_string(), "2:11: undefined variable 'x'");
+/// let err = compile("move 1 2").unwrap_err();
+/// assert_eq!(err.to_string(), "1:8: expected ',', unexpected number 2");
+/// let err = compile("let s = \"ab\"\nrepeat 40 { let s = s + s }\nsay s").unwrap_err();
+/// assert_eq!(err.to_string(), "2:23: string longer than 65536 bytes");
+///
+/// // 错误信息使用当前语言
+/// set_locale(Locale::ZhCn);
+/// let err = compile("say y").unwrap_err();
+/// assert_eq!(err.to_string(), "第 1 行第 5 列: 未定义的变量 'y'");
+/// ```
+pub fn compile(source: &str) -> Result<Vec<Action>, ScriptError> {
+    Ok(compile_with_pos(source)?
+        .into_iter()
+        .map(|(action, _)| action)
+        .collect())
+}
+
+/// 编译并依次执行，动作不合法时报告生成它的语句的位置，之前的动作仍然有效
+/// ```rust
+/// use rust_helloworld::action::Interpreter;
+/// use rust_helloworld::geometry::Point;
+/// use rust_helloworld::i18n::{set_locale, Locale};
+/// use rust_helloworld::script::run;
+///
+/// set_locale(Locale::EnUs);
+/// let mut interpreter = Interpreter::new();
+/// let err = run("move 3, 4\nlet red = 300\ncolor red, 0, 0", &mut interpreter).unwrap_err();
+/// assert_eq!(err.to_string(), "3:1: color channel r out of range 0..=255: 300");
+/// assert_eq!(interpreter.cursor().position, Point::new(3, 4));
+///
+/// run("turn 90\nforward 6\npen up", &mut interpreter).unwrap();
+/// assert_eq!(interpreter.cursor().position, Point::new(3, 10));
+/// assert_eq!(interpreter.cursor().heading, 90);
+/// assert!(!interpreter.cursor().pen_down);
+/// ```
+pub fn run(source: &str, target: &mut dyn ActionTarget) -> Result<(), ScriptError> {
+    for (action, pos) in compile_with_pos(source)? {
+        target
+            .apply(action)
+            .map_err(|err| ScriptError::new(pos, err.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use super::lexer::Token;
+use super::{Pos, ScriptError};
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ExprKind {
+    Int(i64),
+    Str(String),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Expr {
+    pub kind: ExprKind,
+    pub pos: Pos,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StmtKind {
+    Let(String, Expr),
+    Assign(String, Expr),
+    Say(Expr),
+    Move(Expr, Expr),
+    Color(Expr, Expr, Expr),
+    Forward(Expr),
+    Turn(Expr),
+    /// `pen down` 为 true，`pen up` 为 false
+    Pen(bool),
+    Repeat(Expr, Vec<Stmt>),
+    /// `for i in from..to`，不包括 `to`
+    For(String, Expr, Expr, Vec<Stmt>),
+    Proc(String, Vec<String>, Vec<Stmt>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Stmt {
+    pub kind: StmtKind,
+    pub pos: Pos,
+}
+
+/// 语句关键字，不能用作变量名或者过程名
+const KEYWORDS: [&str; 11] = [
+    "let", "say", "move", "color", "forward", "turn", "pen", "repeat", "for", "in", "proc",
+];
+
+/// 括号、取负、运算符和代码块的最大嵌套层数，防止递归下降时栈溢出
+const MAX_NESTING: usize = 128;
+
+struct Parser {
+    tokens: Vec<(Token, Pos)>,
+    index: usize,
+    /// 当前的嵌套层数
+    depth: usize,
+}
+
+pub(crate) fn parse(tokens: Vec<(Token, Pos)>) -> Result<Vec<Stmt>, ScriptError> {
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        depth: 0,
+    };
+    let stmts = parser.stmts()?;
+    match parser.peek() {
+        None => Ok(stmts),
+        Some((token, pos)) => Err(ScriptError::new(pos, unexpected(token))),
+    }
+}
+
+fn unexpected(token: &Token) -> String {
+    match token {
+        Token::Ident(name) => tr("script.unexpected", &[("token", name)]),
+        Token::Number(n) => tr("script.unexpected_number", &[("number", n)]),
+        Token::Str(_) => tr("script.unexpected_string", &[]),
+        Token::Symbol(symbol) => tr("script.unexpected", &[("token", symbol)]),
+        Token::End => tr("script.unexpected_end_of_line", &[]),
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(&Token, Pos)> {
+        self.tokens
+            .get(self.index)
+            .map(|(token, pos)| (token, *pos))
+    }
+
+    /// 当前位置，到末尾时为最后一个记号的位置
+    fn pos(&self) -> Pos {
+        match self.tokens.get(self.index).or(self.tokens.last()) {
+            Some((_, pos)) => *pos,
+            None => Pos { line: 1, column: 1 },
+        }
+    }
+
+    fn next(&mut self) -> Result<(Token, Pos), ScriptError> {
+        match self.tokens.get(self.index) {
+            Some((token, pos)) => {
+                self.index += 1;
+                Ok((token.clone(), *pos))
+            }
+            None => Err(ScriptError::new(
+                self.pos(),
+                tr("script.unexpected_end", &[]),
+            )),
+        }
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some((Token::Symbol(s), _)) if *s == symbol)
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ScriptError> {
+        let (token, pos) = self.next()?;
+        match token {
+            Token::Symbol(s) if s == symbol => Ok(()),
+            token => Err(ScriptError::new(
+                pos,
+                tr(
+                    "script.expected",
+                    &[
+                        ("expected", &format!("'{}'", symbol)),
+                        ("found", &unexpected(&token)),
+                    ],
+                ),
+            )),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ScriptError> {
+        let (token, pos) = self.next()?;
+        match token {
+            Token::Ident(name) if !KEYWORDS.contains(&name.as_str()) => Ok(name),
+            token => Err(ScriptError::new(
+                pos,
+                tr("script.expected_name", &[("found", &unexpected(&token))]),
+            )),
+        }
+    }
+
+    fn enter(&mut self, pos: Pos) -> Result<(), ScriptError> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(ScriptError::new(
+                pos,
+                tr("script.too_nested", &[("max", &MAX_NESTING)]),
+            ));
+        }
+        Ok(())
+    }
+
+    fn skip_ends(&mut self) {
+        while matches!(self.peek(), Some((Token::End, _))) {
+            self.index += 1;
+        }
+    }
+
+    /// 读到 `}` 或者末尾为止
+    fn stmts(&mut self) -> Result<Vec<Stmt>, ScriptError> {
+        let mut stmts = Vec::new();
+        loop {
+            self.skip_ends();
+            if self.peek().is_none() || self.is_symbol("}") {
+                return Ok(stmts);
+            }
+            stmts.push(self.stmt()?);
+            // 语句之后必须换行、分号或者结束块
+            match self.peek() {
+                None | Some((Token::End, _)) => {}
+                Some((Token::Symbol("}"), _)) => {}
+                Some((token, pos)) => return Err(ScriptError::new(pos, unexpected(token))),
+            }
+        }
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, ScriptError> {
+        self.enter(self.pos())?;
+        self.expect("{")?;
+        let stmts = self.stmts()?;
+        self.expect("}")?;
+        self.depth -= 1;
+        Ok(stmts)
+    }
+
+    fn args(&mut self, count: usize) -> Result<Vec<Expr>, ScriptError> {
+        let mut args = vec![self.expr()?];
+        while args.len() < count {
+            self.expect(",")?;
+            args.push(self.expr()?);
+        }
+        Ok(args)
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, ScriptError> {
+        let (token, pos) = self.next()?;
+        let name = match token {
+            Token::Ident(name) => name,
+            token => return Err(ScriptError::new(pos, unexpected(&token))),
+        };
+        let kind = match name.as_str() {
+            "let" => {
+                let name = self.ident()?;
+                self.expect("=")?;
+                StmtKind::Let(name, self.expr()?)
+            }
+            "say" => StmtKind::Say(self.expr()?),
+            "move" => {
+                let mut args = self.args(2)?.into_iter();
+                StmtKind::Move(args.next().unwrap(), args.next().unwrap())
+            }
+            "color" => {
+                let mut args = self.args(3)?.into_iter();
+                StmtKind::Color(
+                    args.next().unwrap(),
+                    args.next().unwrap(),
+                    args.next().unwrap(),
+                )
+            }
+            "forward" => StmtKind::Forward(self.expr()?),
+            "turn" => StmtKind::Turn(self.expr()?),
+            "pen" => match self.next()? {
+                (Token::Ident(word), _) if word == "up" => StmtKind::Pen(false),
+                (Token::Ident(word), _) if word == "down" => StmtKind::Pen(true),
+                (token, pos) => {
+                    return Err(ScriptError::new(
+                        pos,
+                        tr(
+                            "script.expected",
+                            &[
+                                ("expected", &"'up' / 'down'"),
+                                ("found", &unexpected(&token)),
+                            ],
+                        ),
+                    ))
+                }
+            },
+            "repeat" => {
+                let count = self.expr()?;
+                StmtKind::Repeat(count, self.block()?)
+            }
+            "for" => {
+                let var = self.ident()?;
+                match self.next()? {
+                    (Token::Ident(word), _) if word == "in" => {}
+                    (token, pos) => {
+                        return Err(ScriptError::new(
+                            pos,
+                            tr(
+                                "script.expected",
+                                &[("expected", &"'in'"), ("found", &unexpected(&token))],
+                            ),
+                        ))
+                    }
+                }
+                let from = self.expr()?;
+                self.expect("..")?;
+                let to = self.expr()?;
+                StmtKind::For(var, from, to, self.block()?)
+            }
+            "proc" => {
+                let name = self.ident()?;
+                self.expect("(")?;
+                let mut params = Vec::new();
+                while !self.is_symbol(")") {
+                    if !params.is_empty() {
+                        self.expect(",")?;
+                    }
+                    params.push(self.ident()?);
+                }
+                self.expect(")")?;
+                StmtKind::Proc(name, params, self.block()?)
+            }
+            "in" => {
+                return Err(ScriptError::new(
+                    pos,
+                    tr("script.unexpected", &[("token", &"in")]),
+                ))
+            }
+            _ if self.is_symbol("=") => {
+                self.index += 1;
+                StmtKind::Assign(name, self.expr()?)
+            }
+            _ if self.is_symbol("(") => {
+                self.index += 1;
+                let mut args = Vec::new();
+                while !self.is_symbol(")") {
+                    if !args.is_empty() {
+                        self.expect(",")?;
+                    }
+                    args.push(self.expr()?);
+                }
+                self.expect(")")?;
+                StmtKind::Call(name, args)
+            }
+            _ => {
+                return Err(ScriptError::new(
+                    pos,
+                    tr("script.unknown_command", &[("name", &name)]),
+                ))
+            }
+        };
+        Ok(Stmt { kind, pos })
+    }
+
+    fn expr(&mut self) -> Result<Expr, ScriptError> {
+        self.binary(0)
+    }
+
+    /// 优先级 0 为加减，1 为乘除取余
+    /// 每个运算符都让左边的表达式树深一层，所以也计入嵌套层数
+    fn binary(&mut self, level: usize) -> Result<Expr, ScriptError> {
+        let depth = self.depth;
+        let mut left = if level == 0 {
+            self.binary(1)?
+        } else {
+            self.unary()?
+        };
+        loop {
+            let op = match (level, self.peek()) {
+                (0, Some((Token::Symbol("+"), _))) => Op::Add,
+                (0, Some((Token::Symbol("-"), _))) => Op::Sub,
+                (1, Some((Token::Symbol("*"), _))) => Op::Mul,
+                (1, Some((Token::Symbol("/"), _))) => Op::Div,
+                (1, Some((Token::Symbol("%"), _))) => Op::Rem,
+                _ => {
+                    self.depth = depth;
+                    return Ok(left);
+                }
+            };
+            let (_, pos) = self.next()?;
+            self.enter(pos)?;
+            let right = if level == 0 {
+                self.binary(1)?
+            } else {
+                self.unary()?
+            };
+            left = Expr {
+                kind: ExprKind::Binary(op, Box::new(left), Box::new(right)),
+                pos,
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ScriptError> {
+        let (token, pos) = self.next()?;
+        let kind = match token {
+            Token::Number(n) => ExprKind::Int(n),
+            Token::Str(s) => ExprKind::Str(s),
+            Token::Ident(name) if !KEYWORDS.contains(&name.as_str()) => ExprKind::Var(name),
+            Token::Symbol("-") => {
+                self.enter(pos)?;
+                let value = self.unary()?;
+                self.depth -= 1;
+                ExprKind::Neg(Box::new(value))
+            }
+            Token::Symbol("(") => {
+                self.enter(pos)?;
+                let expr = self.expr()?;
+                self.expect(")")?;
+                self.depth -= 1;
+                return Ok(expr);
+            }
+            token => {
+                return Err(ScriptError::new(
+                    pos,
+                    tr("script.expected_value", &[("found", &unexpected(&token))]),
+                ))
+            }
+        };
+        Ok(Expr { kind, pos })
+    }
+}
@@ -0,0 +1,322 @@
+//! 前台操作的 HTTP 接口，需要开启 `server` feature
+//!
+//! 请求和响应都是 JSON，空的请求体等同于 `{}`；操作人通过 `X-Actor` 请求头传入，缺省为 `api`。
+//! 路径存在但方法不支持时返回 405，并在 `Allow` 响应头中列出支持的方法。
+//!
+//! | 方法 | 路径 | 说明 |
+//! | --- | --- | --- |
+//! | GET / POST | `/waitlist` | 查看候位名单 / 加入候位 `{name, size}` |
+//! | POST | `/waitlist/{id}/seat` | 入座 `{table}` |
+//! | GET / POST | `/reservations` | 查看预订 / 预订 `{name, size, time}` |
+//! | DELETE | `/reservations/{id}` | 取消预订 |
+//! | GET / POST | `/orders` | 查看订单 / 点单 `{table, covers, items}` |
+//! | GET / PUT | `/orders/{id}` | 查看订单 / 修改菜品 `{items}` |
+//! | POST | `/orders/{id}/serve` | 上菜 |
+//! | POST | `/orders/{id}/void` | 作废 `{reason}` |
+//! | POST | `/orders/{id}/payments` | 收款 `{tip}` |
+//! | GET | `/payments` | 查看付款 |
+use std::error::Error;
+use std::net::SocketAddr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Response};
+
+use crate::front_of_house::hosting;
+use crate::front_of_house::serving::{self, OrderItem};
+use crate::storage::{Storage, StorageError};
+
+/// 一次请求的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: Value,
+    /// 额外的响应头，比如 405 时的 `Allow`
+    pub headers: Vec<(String, String)>,
+}
+
+impl Reply {
+    fn ok<T: Serialize>(value: T) -> Reply {
+        Reply::with_status(200, value)
+    }
+
+    fn with_status<T: Serialize>(status: u16, value: T) -> Reply {
+        match serde_json::to_value(value) {
+            Ok(body) => Reply {
+                status,
+                body,
+                headers: Vec::new(),
+            },
+            Err(err) => Reply::error(500, &err.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Reply {
+        Reply {
+            status,
+            body: json!({ "error": message }),
+            headers: Vec::new(),
+        }
+    }
+
+    fn method_not_allowed(allow: &[&str]) -> Reply {
+        let mut reply = Reply::error(405, "method not allowed");
+        reply.headers.push(("Allow".to_string(), allow.join(", ")));
+        reply
+    }
+
+    fn not_found() -> Reply {
+        Reply::error(404, "not found")
+    }
+
+    /// 结果为 None 时返回 404
+    fn found<T: Serialize>(value: Option<T>) -> Reply {
+        match value {
+            Some(value) => Reply::ok(value),
+            None => Reply::not_found(),
+        }
+    }
+}
+
+impl From<StorageError> for Reply {
+    fn from(err: StorageError) -> Reply {
+        let status = match err {
+            StorageError::InvalidTransition { .. } => 409,
+            _ => 500,
+        };
+        Reply::error(status, &err.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct NewParty {
+    name: String,
+    size: u32,
+}
+
+#[derive(Deserialize)]
+struct SeatRequest {
+    table: u32,
+}
+
+#[derive(Deserialize)]
+struct NewReservation {
+    name: String,
+    size: u32,
+    time: u64,
+}
+
+#[derive(Deserialize)]
+struct NewOrder {
+    table: u32,
+    covers: u32,
+    items: Vec<OrderItem>,
+}
+
+#[derive(Deserialize)]
+struct ModifyRequest {
+    items: Vec<OrderItem>,
+}
+
+#[derive(Deserialize)]
+struct VoidRequest {
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Deserialize)]
+struct PaymentRequest {
+    #[serde(default)]
+    tip: u64,
+}
+
+/// 空的请求体当作 `{}`，字段都取缺省值
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, Reply> {
+    let body = if body.trim().is_empty() { "{}" } else { body };
+    serde_json::from_str(body).map_err(|err| Reply::error(400, &err.to_string()))
+}
+
+fn parse_id(id: &str) -> Result<u64, Reply> {
+    id.parse().map_err(|_| Reply::not_found())
+}
+
+/// 把一个请求路由到对应的前台操作上
+/// ```rust
+/// use rust_helloworld::server::handle;
+/// use rust_helloworld::storage::MemoryStorage;
+///
+/// let mut store = MemoryStorage::new();
+/// let reply = handle(&mut store, "POST", "/waitlist", "host", r#"{"name": "王五", "size": 3}"#);
+/// assert_eq!(reply.status, 201);
+/// assert_eq!(reply.body["name"], "王五");
+///
+/// let reply = handle(&mut store, "GET", "/waitlist", "host", "");
+/// assert_eq!(reply.body.as_array().unwrap().len(), 1);
+/// ```
+pub fn handle(store: &mut dyn Storage, method: &str, url: &str, actor: &str, body: &str) -> Reply {
+    match route(store, method, url, actor, body) {
+        Ok(reply) => reply,
+        Err(reply) => reply,
+    }
+}
+
+fn route(
+    store: &mut dyn Storage,
+    method: &str,
+    url: &str,
+    actor: &str,
+    body: &str,
+) -> Result<Reply, Reply> {
+    let path = url.split('?').next().unwrap_or("");
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // 先按路径匹配，路径存在但方法不对时返回 405
+    let reply = match segments.as_slice() {
+        ["waitlist"] => match method {
+            "GET" => Reply::ok(store.waitlist()?),
+            "POST" => {
+                let req: NewParty = parse(body)?;
+                Reply::with_status(201, hosting::add_to_waitlist(store, &req.name, req.size)?)
+            }
+            _ => Reply::method_not_allowed(&["GET", "POST"]),
+        },
+        ["waitlist", id, "seat"] => match method {
+            "POST" => {
+                let req: SeatRequest = parse(body)?;
+                Reply::found(hosting::seat_at_table(store, parse_id(id)?, req.table)?)
+            }
+            _ => Reply::method_not_allowed(&["POST"]),
+        },
+        ["reservations"] => match method {
+            "GET" => Reply::ok(store.reservations()?),
+            "POST" => {
+                let req: NewReservation = parse(body)?;
+                let reservation = hosting::reserve(store, &req.name, req.size, req.time)?;
+                Reply::with_status(201, reservation)
+            }
+            _ => Reply::method_not_allowed(&["GET", "POST"]),
+        },
+        ["reservations", id] => match method {
+            "DELETE" => Reply::found(hosting::cancel_reservation(store, parse_id(id)?)?),
+            _ => Reply::method_not_allowed(&["DELETE"]),
+        },
+        ["orders"] => match method {
+            "GET" => Reply::ok(store.orders()?),
+            "POST" => {
+                let req: NewOrder = parse(body)?;
+                let order = serving::take_order(store, actor, req.table, req.covers, req.items)?;
+                Reply::with_status(201, order)
+            }
+            _ => Reply::method_not_allowed(&["GET", "POST"]),
+        },
+        ["orders", id] => match method {
+            "GET" => Reply::found(store.order(parse_id(id)?)?),
+            "PUT" => {
+                let req: ModifyRequest = parse(body)?;
+                Reply::found(serving::modify_order(
+                    store,
+                    actor,
+                    parse_id(id)?,
+                    req.items,
+                )?)
+            }
+            _ => Reply::method_not_allowed(&["GET", "PUT"]),
+        },
+        ["orders", id, "serve"] => match method {
+            "POST" => Reply::found(serving::serve_order(store, actor, parse_id(id)?)?),
+            _ => Reply::method_not_allowed(&["POST"]),
+        },
+        ["orders", id, "void"] => match method {
+            "POST" => {
+                let req: VoidRequest = parse(body)?;
+                Reply::found(serving::void_order(
+                    store,
+                    actor,
+                    parse_id(id)?,
+                    &req.reason,
+                )?)
+            }
+            _ => Reply::method_not_allowed(&["POST"]),
+        },
+        ["orders", id, "payments"] => match method {
+            "POST" => {
+                let req: PaymentRequest = parse(body)?;
+                match serving::take_payment(store, actor, parse_id(id)?, req.tip)? {
+                    Some(payment) => Reply::with_status(201, payment),
+                    None => Reply::not_found(),
+                }
+            }
+            _ => Reply::method_not_allowed(&["POST"]),
+        },
+        ["payments"] => match method {
+            "GET" => Reply::ok(store.payments()?),
+            _ => Reply::method_not_allowed(&["GET"]),
+        },
+        _ => Reply::not_found(),
+    };
+    Ok(reply)
+}
+
+/// 单线程的 HTTP 服务，按顺序处理请求
+pub struct Server {
+    http: tiny_http::Server,
+    store: Box<dyn Storage + Send>,
+}
+
+impl Server {
+    /// 监听地址，端口为 0 时由系统分配
+    pub fn bind(
+        addr: &str,
+        store: Box<dyn Storage + Send>,
+    ) -> Result<Server, Box<dyn Error + Send + Sync + 'static>> {
+        let http = tiny_http::Server::http(addr)?;
+        Ok(Server { http, store })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// 处理请求，直到监听的 socket 被关闭
+    pub fn run(mut self) {
+        for request in self.http.incoming_requests() {
+            respond(self.store.as_mut(), request);
+        }
+    }
+}
+
+fn respond(store: &mut dyn Storage, mut request: Request) {
+    let actor = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("X-Actor"))
+        .map(|header| header.value.to_string())
+        .unwrap_or_else(|| String::from("api"));
+
+    let mut body = String::new();
+    let reply = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => handle(
+            store,
+            request.method().as_str(),
+            request.url(),
+            &actor,
+            &body,
+        ),
+        Err(err) => Reply::error(400, &err.to_string()),
+    };
+
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let mut response = Response::from_string(reply.body.to_string())
+        .with_status_code(reply.status)
+        .with_header(content_type);
+    for (field, value) in &reply.headers {
+        // 非法的响应头直接丢弃
+        if let Ok(header) = Header::from_bytes(field.as_bytes(), value.as_bytes()) {
+            response.add_header(header);
+        }
+    }
+    // 客户端已经断开时无需处理
+    let _ = request.respond(response);
+}
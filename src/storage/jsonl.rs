@@ -0,0 +1,230 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{MemoryStorage, Storage, StorageError};
+use crate::events::OrderEvent;
+use crate::front_of_house::hosting::{Party, Reservation};
+use crate::front_of_house::serving::{Order, Payment};
+
+/// 数据文件中的一行
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Record {
+    PartyAdded(Party),
+    PartyRemoved {
+        id: u64,
+    },
+    ReservationSaved(Reservation),
+    ReservationRemoved {
+        id: u64,
+    },
+    OrderSaved(Order),
+    /// 旧版本写入的付款，现在付款由 `Paid` 事件得出，回放时忽略
+    PaymentSaved(Payment),
+    Event(OrderEvent),
+}
+
+/// 追加写入的 JSON-lines 文件存储
+///
+/// 读操作直接走内存中的副本，每次修改都会先追加一行记录再更新内存。
+/// 进程在写入途中退出时，文件末尾会留下没有换行的半行，打开时会把它截掉。
+/// ```rust
+/// use rust_helloworld::front_of_house::hosting::add_to_waitlist;
+/// use rust_helloworld::storage::{JsonLinesStorage, Storage};
+///
+/// let path = std::env::temp_dir().join("rust-helloworld-jsonl-doc.jsonl");
+/// let _ = std::fs::remove_file(&path);
+///
+/// let mut store = JsonLinesStorage::open(&path).unwrap();
+/// add_to_waitlist(&mut store, "李四", 2).unwrap();
+/// drop(store);
+///
+/// // 重新打开文件，回放出之前的候位名单
+/// let store = JsonLinesStorage::open(&path).unwrap();
+/// assert_eq!(store.waitlist().unwrap()[0].name, "李四");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct JsonLinesStorage {
+    file: File,
+    state: MemoryStorage,
+}
+
+impl JsonLinesStorage {
+    /// 打开数据文件并回放其中的所有记录，文件不存在时新建
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<JsonLinesStorage, StorageError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let replayed = replay(&file)?;
+        if replayed.len < file.metadata()?.len() {
+            file.set_len(replayed.len)?;
+        }
+        // 最后一条完整的记录缺少换行时补上，免得下一条记录接在同一行
+        if replayed.unterminated {
+            file.write_all(b"\n")?;
+        }
+        Ok(JsonLinesStorage {
+            file,
+            state: replayed.state,
+        })
+    }
+
+    /// 只读打开数据文件，回放成内存中的副本，文件不存在时报错
+    ///
+    /// 不会创建或者修改文件，对返回的副本所做的修改也不会写回文件。
+    /// ```rust
+    /// use rust_helloworld::storage::JsonLinesStorage;
+    ///
+    /// let path = std::env::temp_dir().join("rust-helloworld-jsonl-load-missing.jsonl");
+    /// assert!(JsonLinesStorage::load(&path).is_err());
+    /// assert!(!path.exists());
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<MemoryStorage, StorageError> {
+        Ok(replay(&File::open(path)?)?.state)
+    }
+
+    fn append(&mut self, record: &Record) -> Result<(), StorageError> {
+        let mut line = serde_json::to_string(record).map_err(std::io::Error::from)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+struct Replayed {
+    state: MemoryStorage,
+    /// 完整记录的字节数，之后是写了一半的最后一行
+    len: u64,
+    /// 最后一条完整的记录后面没有换行
+    unterminated: bool,
+}
+
+/// 逐行回放，中间的行无法解析时返回 [`StorageError::Corrupt`]，
+/// 没有换行且无法解析的最后一行是写了一半的记录，直接忽略
+fn replay(file: &File) -> Result<Replayed, StorageError> {
+    let mut replayed = Replayed {
+        state: MemoryStorage::new(),
+        len: 0,
+        unterminated: false,
+    };
+    let mut reader = BufReader::new(file);
+    let mut buf = Vec::new();
+    let mut index = 0;
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            return Ok(replayed);
+        }
+        index += 1;
+        let terminated = buf.ends_with(b"\n");
+        let record = std::str::from_utf8(&buf)
+            .map_err(|err| err.to_string())
+            .and_then(|line| match line.trim() {
+                "" => Ok(None),
+                line => serde_json::from_str(line)
+                    .map(Some)
+                    .map_err(|err| err.to_string()),
+            });
+        let record = match record {
+            Ok(record) => record,
+            Err(_) if !terminated => return Ok(replayed),
+            Err(message) => {
+                return Err(StorageError::Corrupt {
+                    line: index,
+                    message,
+                })
+            }
+        };
+        if let Some(record) = record {
+            apply(&mut replayed.state, record)?;
+        }
+        replayed.len += read as u64;
+        replayed.unterminated = !terminated;
+    }
+}
+
+fn apply(state: &mut MemoryStorage, record: Record) -> Result<(), StorageError> {
+    match record {
+        Record::PartyAdded(party) => state.push_party(party),
+        Record::PartyRemoved { id } => state.remove_party(id).map(|_| ()),
+        Record::ReservationSaved(reservation) => state.put_reservation(reservation),
+        Record::ReservationRemoved { id } => state.remove_reservation(id).map(|_| ()),
+        Record::OrderSaved(order) => state.put_order(order),
+        Record::PaymentSaved(_) => Ok(()),
+        Record::Event(event) => state.append_event(event),
+    }
+}
+
+impl Storage for JsonLinesStorage {
+    fn next_id(&mut self) -> Result<u64, StorageError> {
+        self.state.next_id()
+    }
+
+    fn push_party(&mut self, party: Party) -> Result<(), StorageError> {
+        self.append(&Record::PartyAdded(party.clone()))?;
+        self.state.push_party(party)
+    }
+
+    fn remove_party(&mut self, id: u64) -> Result<Option<Party>, StorageError> {
+        if !self.state.waitlist()?.iter().any(|party| party.id == id) {
+            return Ok(None);
+        }
+        self.append(&Record::PartyRemoved { id })?;
+        self.state.remove_party(id)
+    }
+
+    fn waitlist(&self) -> Result<Vec<Party>, StorageError> {
+        self.state.waitlist()
+    }
+
+    fn put_reservation(&mut self, reservation: Reservation) -> Result<(), StorageError> {
+        self.append(&Record::ReservationSaved(reservation.clone()))?;
+        self.state.put_reservation(reservation)
+    }
+
+    fn remove_reservation(&mut self, id: u64) -> Result<Option<Reservation>, StorageError> {
+        if !self.state.reservations()?.iter().any(|r| r.id == id) {
+            return Ok(None);
+        }
+        self.append(&Record::ReservationRemoved { id })?;
+        self.state.remove_reservation(id)
+    }
+
+    fn reservations(&self) -> Result<Vec<Reservation>, StorageError> {
+        self.state.reservations()
+    }
+
+    fn put_order(&mut self, order: Order) -> Result<(), StorageError> {
+        self.append(&Record::OrderSaved(order.clone()))?;
+        self.state.put_order(order)
+    }
+
+    fn order(&self, id: u64) -> Result<Option<Order>, StorageError> {
+        self.state.order(id)
+    }
+
+    fn orders(&self) -> Result<Vec<Order>, StorageError> {
+        self.state.orders()
+    }
+
+    fn payments(&self) -> Result<Vec<Payment>, StorageError> {
+        self.state.payments()
+    }
+
+    fn append_event(&mut self, event: OrderEvent) -> Result<(), StorageError> {
+        self.append(&Record::Event(event.clone()))?;
+        self.state.append_event(event)
+    }
+
+    fn events(&self) -> Result<Vec<OrderEvent>, StorageError> {
+        self.state.events()
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use super::{Storage, StorageError};
+use crate::events::OrderEvent;
+use crate::front_of_house::hosting::{Party, Reservation};
+use crate::front_of_house::serving::{Order, Payment};
+
+/// 内存存储，进程退出后数据即丢失
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage {
+    last_id: u64,
+    waitlist: Vec<Party>,
+    reservations: BTreeMap<u64, Reservation>,
+    orders: BTreeMap<u64, Order>,
+    payments: BTreeMap<u64, Payment>,
+    events: Vec<OrderEvent>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+
+    // 回放已有记录时，保证之后分配的编号不会与之重复
+    fn see_id(&mut self, id: u64) {
+        self.last_id = self.last_id.max(id);
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn next_id(&mut self) -> Result<u64, StorageError> {
+        self.last_id += 1;
+        Ok(self.last_id)
+    }
+
+    fn push_party(&mut self, party: Party) -> Result<(), StorageError> {
+        self.see_id(party.id);
+        self.waitlist.push(party);
+        Ok(())
+    }
+
+    fn remove_party(&mut self, id: u64) -> Result<Option<Party>, StorageError> {
+        let index = self.waitlist.iter().position(|party| party.id == id);
+        Ok(index.map(|i| self.waitlist.remove(i)))
+    }
+
+    fn waitlist(&self) -> Result<Vec<Party>, StorageError> {
+        Ok(self.waitlist.clone())
+    }
+
+    fn put_reservation(&mut self, reservation: Reservation) -> Result<(), StorageError> {
+        self.see_id(reservation.id);
+        self.reservations.insert(reservation.id, reservation);
+        Ok(())
+    }
+
+    fn remove_reservation(&mut self, id: u64) -> Result<Option<Reservation>, StorageError> {
+        Ok(self.reservations.remove(&id))
+    }
+
+    fn reservations(&self) -> Result<Vec<Reservation>, StorageError> {
+        Ok(self.reservations.values().cloned().collect())
+    }
+
+    fn put_order(&mut self, order: Order) -> Result<(), StorageError> {
+        self.see_id(order.id);
+        self.orders.insert(order.id, order);
+        Ok(())
+    }
+
+    fn order(&self, id: u64) -> Result<Option<Order>, StorageError> {
+        Ok(self.orders.get(&id).cloned())
+    }
+
+    fn orders(&self) -> Result<Vec<Order>, StorageError> {
+        Ok(self.orders.values().cloned().collect())
+    }
+
+    fn payments(&self) -> Result<Vec<Payment>, StorageError> {
+        Ok(self.payments.values().cloned().collect())
+    }
+
+    fn append_event(&mut self, event: OrderEvent) -> Result<(), StorageError> {
+        self.see_id(event.order_id);
+        if let Some(payment) = event.payment() {
+            self.see_id(payment.id);
+            self.payments.insert(payment.id, payment);
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    fn events(&self) -> Result<Vec<OrderEvent>, StorageError> {
+        Ok(self.events.clone())
+    }
+}
@@ -0,0 +1,96 @@
+//! 餐厅状态的存储：候位名单、预订、订单、付款以及订单事件日志
+//!
+//! [`MemoryStorage`] 只保存在内存里，[`JsonLinesStorage`] 把每一次修改追加写入文件，
+//! 进程重启后重新打开同一个文件即可回放出当天的营业状态。
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use crate::events::OrderEvent;
+use crate::front_of_house::hosting::{Party, Reservation};
+use crate::front_of_house::serving::{Order, OrderStatus, Payment};
+use crate::i18n::tr;
+
+mod jsonl;
+mod memory;
+
+pub use self::jsonl::JsonLinesStorage;
+pub use self::memory::MemoryStorage;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    /// 数据文件第 `line` 行无法解析
+    Corrupt {
+        line: usize,
+        message: String,
+    },
+    /// 订单处于 `status` 状态，不允许发生 `event` 事件
+    InvalidTransition {
+        order_id: u64,
+        status: OrderStatus,
+        event: &'static str,
+    },
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            StorageError::Io(err) => tr("error.storage.io", &[("error", err)]),
+            StorageError::Corrupt { line, message } => tr(
+                "error.storage.corrupt",
+                &[("line", line), ("message", message)],
+            ),
+            StorageError::InvalidTransition {
+                order_id,
+                status,
+                event,
+            } => tr(
+                "error.storage.invalid_transition",
+                &[
+                    ("id", order_id),
+                    ("status", &status.label()),
+                    ("event", &tr(&format!("order.event.{}", event), &[])),
+                ],
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> StorageError {
+        StorageError::Io(err)
+    }
+}
+
+/// 存储后端需要实现的特征
+///
+/// `put_*` 方法按编号覆盖已有记录，没有则新增。
+pub trait Storage {
+    /// 分配一个新的记录编号
+    fn next_id(&mut self) -> Result<u64, StorageError>;
+
+    /// 加入候位名单末尾
+    fn push_party(&mut self, party: Party) -> Result<(), StorageError>;
+    fn remove_party(&mut self, id: u64) -> Result<Option<Party>, StorageError>;
+    /// 按加入顺序返回候位名单
+    fn waitlist(&self) -> Result<Vec<Party>, StorageError>;
+
+    fn put_reservation(&mut self, reservation: Reservation) -> Result<(), StorageError>;
+    fn remove_reservation(&mut self, id: u64) -> Result<Option<Reservation>, StorageError>;
+    fn reservations(&self) -> Result<Vec<Reservation>, StorageError>;
+
+    fn put_order(&mut self, order: Order) -> Result<(), StorageError>;
+    fn order(&self, id: u64) -> Result<Option<Order>, StorageError>;
+    fn orders(&self) -> Result<Vec<Order>, StorageError>;
+
+    /// 按编号返回付款，付款由事件日志中的 `Paid` 事件得出
+    fn payments(&self) -> Result<Vec<Payment>, StorageError>;
+
+    /// 追加一条订单事件，已经写入的事件不会再被修改，`Paid` 事件同时记下付款
+    fn append_event(&mut self, event: OrderEvent) -> Result<(), StorageError>;
+    /// 按写入顺序返回全部订单事件
+    fn events(&self) -> Result<Vec<OrderEvent>, StorageError>;
+}
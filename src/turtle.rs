@@ -0,0 +1,252 @@
+//! 海龟绘图：海龟带着笔在平面上前进、转向，笔放下时留下轨迹
+//!
+//! 海龟也是一个 [`ActionTarget`]：`Forward`、`Turn`、`PenUp` 和 `PenDown` 对应海龟的
+//! 前进、右转和抬笔落笔，`MoveTo` 让它直接走到某个点，`ChangeColorRGB` 换笔的颜色，
+//! `Say` 在当前位置写字，所以动作脚本也可以用来画画。
+//!
+//! y 轴向下，朝向以度为单位，0 度朝右，右转时角度增大。
+use crate::action::{Action, ActionError, ActionTarget};
+use crate::draw::{Draw, DrawContext, RenderTarget, SvgDocument};
+use crate::geometry::Point;
+use crate::kinds::Rgba;
+use crate::script::{self, ScriptError};
+
+/// 导出 SVG 时图形四周留的空白
+const SVG_MARGIN: f64 = 10.0;
+
+/// 海龟留下的痕迹
+#[derive(Debug, Clone, PartialEq)]
+enum Mark {
+    Path {
+        points: Vec<Point<f64>>,
+        color: Rgba,
+        width: f64,
+    },
+    Text {
+        position: Point<f64>,
+        text: String,
+        color: Rgba,
+    },
+}
+
+/// ```rust
+/// use rust_helloworld::geometry::Point;
+/// use rust_helloworld::kinds::Rgba;
+/// use rust_helloworld::turtle::Turtle;
+///
+/// let mut turtle = Turtle::new();
+/// for _ in 0..4 {
+///     turtle.forward(10.0);
+///     turtle.right(90.0);
+/// }
+/// turtle.pen_up();
+/// turtle.forward(20.0);
+/// assert_eq!(turtle.position(), Point::new(20.0, 0.0));
+///
+/// // 笔抬起时不留痕迹，正方形是一条连续的折线
+/// let svg = turtle.to_svg();
+/// assert_eq!(svg.matches("<polyline").count(), 1);
+///
+/// turtle.pen_down();
+/// turtle.run_script("color 255, 0, 0\nmove 20, 20\nsay \"done\"").unwrap();
+/// assert_eq!(turtle.color(), Rgba::rgb(255, 0, 0));
+/// assert!(turtle.to_svg().contains(r#"stroke="rgb(255,0,0)""#));
+///
+/// let err = turtle.run_script("color 256, 0, 0").unwrap_err();
+/// assert_eq!(err.pos.line, 1);
+///
+/// // 脚本也可以让海龟前进和转向
+/// let mut scripted = Turtle::new();
+/// scripted.run_script("repeat 4 { forward 10; turn 90 }\npen up\nforward 20").unwrap();
+/// assert_eq!(scripted.position(), Point::new(20.0, 0.0));
+/// assert!(!scripted.is_pen_down());
+/// assert_eq!(scripted.to_svg().matches("<polyline").count(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turtle {
+    position: Point<f64>,
+    heading: f64,
+    pen_down: bool,
+    color: Rgba,
+    width: f64,
+    marks: Vec<Mark>,
+}
+
+impl Default for Turtle {
+    fn default() -> Turtle {
+        Turtle::new()
+    }
+}
+
+impl Turtle {
+    /// 在原点朝右，笔放下，黑色，线宽为 1
+    pub fn new() -> Turtle {
+        Turtle {
+            position: Point::origin(),
+            heading: 0.0,
+            pen_down: true,
+            color: Rgba::BLACK,
+            width: 1.0,
+            marks: Vec::new(),
+        }
+    }
+
+    pub fn position(&self) -> Point<f64> {
+        self.position
+    }
+
+    pub fn heading(&self) -> f64 {
+        self.heading
+    }
+
+    pub fn color(&self) -> Rgba {
+        self.color
+    }
+
+    pub fn is_pen_down(&self) -> bool {
+        self.pen_down
+    }
+
+    pub fn pen_up(&mut self) {
+        self.pen_down = false;
+    }
+
+    pub fn pen_down(&mut self) {
+        self.pen_down = true;
+    }
+
+    pub fn set_color(&mut self, color: Rgba) {
+        self.color = color;
+    }
+
+    pub fn set_width(&mut self, width: f64) {
+        self.width = width;
+    }
+
+    /// 朝向取 0 到 360 度
+    pub fn set_heading(&mut self, degrees: f64) {
+        self.heading = degrees.rem_euclid(360.0);
+    }
+
+    pub fn right(&mut self, degrees: f64) {
+        self.set_heading(self.heading + degrees);
+    }
+
+    pub fn left(&mut self, degrees: f64) {
+        self.set_heading(self.heading - degrees);
+    }
+
+    pub fn forward(&mut self, distance: f64) {
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        // 消除 sin/cos 的舍入误差，让水平和竖直方向的线保持整齐
+        let round = |v: f64| (v * 1e9).round() / 1e9;
+        let target = self.position + Point::new(round(cos), round(sin)) * distance;
+        self.goto(target);
+    }
+
+    pub fn back(&mut self, distance: f64) {
+        self.forward(-distance);
+    }
+
+    /// 直接走到 `target`，不改变朝向
+    pub fn goto(&mut self, target: Point<f64>) {
+        if self.pen_down {
+            let (from, color, width) = (self.position, self.color, self.width);
+            match self.marks.last_mut() {
+                // 接着上一段画，颜色和线宽都相同时合并成一条折线
+                Some(Mark::Path {
+                    points,
+                    color: c,
+                    width: w,
+                }) if points.last() == Some(&from) && *c == color && *w == width => {
+                    points.push(target)
+                }
+                _ => self.marks.push(Mark::Path {
+                    points: vec![from, target],
+                    color,
+                    width,
+                }),
+            }
+        }
+        self.position = target;
+    }
+
+    /// 在当前位置写字
+    pub fn write(&mut self, text: &str) {
+        self.marks.push(Mark::Text {
+            position: self.position,
+            text: text.to_string(),
+            color: self.color,
+        });
+    }
+
+    /// 清除所有痕迹，海龟的状态不变
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    /// 在海龟上执行动作脚本，见 [`script`]
+    pub fn run_script(&mut self, source: &str) -> Result<(), ScriptError> {
+        script::run(source, self)
+    }
+
+    /// 导出为 SVG，画布大小按所有轨迹的范围加上边距计算
+    pub fn to_svg(&self) -> String {
+        let points = self.marks.iter().flat_map(|mark| match mark {
+            Mark::Path { points, .. } => points.clone(),
+            Mark::Text { position, .. } => vec![*position],
+        });
+        let (mut min, mut max) = (self.position, self.position);
+        for p in points {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let width = (max.x - min.x + 2.0 * SVG_MARGIN).ceil() as u32;
+        let height = (max.y - min.y + 2.0 * SVG_MARGIN).ceil() as u32;
+        let mut svg = SvgDocument::new(width, height);
+        let ctx = DrawContext::new().translate(SVG_MARGIN - min.x, SVG_MARGIN - min.y);
+        self.draw(&ctx, &mut svg);
+        svg.to_string()
+    }
+}
+
+impl ActionTarget for Turtle {
+    fn apply(&mut self, action: Action) -> Result<(), ActionError> {
+        action.validate()?;
+        match action {
+            Action::Say(text) => self.write(&text),
+            Action::MoveTo(x, y) => self.goto(Point::new(x as f64, y as f64)),
+            // 已经检查过范围
+            Action::ChangeColorRGB(r, g, b) => self.color = Rgba::rgb(r as u8, g as u8, b as u8),
+            Action::Forward(distance) => self.forward(distance as f64),
+            Action::Turn(degrees) => self.right(degrees as f64),
+            Action::PenUp => self.pen_up(),
+            Action::PenDown => self.pen_down(),
+        }
+        Ok(())
+    }
+}
+
+/// 轨迹按各自的颜色和线宽绘制，只使用上下文的变换
+impl Draw for Turtle {
+    fn draw(&self, ctx: &DrawContext, target: &mut dyn RenderTarget) {
+        for mark in &self.marks {
+            match mark {
+                Mark::Path {
+                    points,
+                    color,
+                    width,
+                } => {
+                    let points: Vec<Point<f64>> =
+                        points.iter().map(|p| ctx.transform.apply(*p)).collect();
+                    target.polyline(&points, *width, *color);
+                }
+                Mark::Text {
+                    position,
+                    text,
+                    color,
+                } => target.text(ctx.transform.apply(*position), text, *color),
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fs;
+
+use rust_helloworld::canvas::Canvas;
+use rust_helloworld::kinds::Rgba;
+
+// 2×2 的画布：不透明红色、半透明蓝色、透明、白色
+fn sample() -> Canvas {
+    let mut canvas = Canvas::new(2, 2);
+    canvas.blend_pixel(0, 0, Rgba::rgb(255, 0, 0), 1.0);
+    canvas.blend_pixel(1, 0, Rgba::new(0, 0, 255, 128), 1.0);
+    canvas.blend_pixel(1, 1, Rgba::WHITE, 1.0);
+    canvas
+}
+
+#[test]
+fn pixel_reads_row_major() {
+    let canvas = sample();
+    assert_eq!(canvas.pixel(0, 0), Some(Rgba::rgb(255, 0, 0)));
+    assert_eq!(canvas.pixel(1, 0), Some(Rgba::new(0, 0, 255, 128)));
+    assert_eq!(canvas.pixel(0, 1), Some(Rgba::TRANSPARENT));
+    assert_eq!(canvas.pixel(1, 1), Some(Rgba::WHITE));
+    assert_eq!(canvas.pixel(2, 0), None);
+    assert_eq!(canvas.pixel(0, 2), None);
+    assert_eq!(canvas.pixel(u32::MAX, u32::MAX), None);
+}
+
+#[test]
+fn ppm_blends_onto_white() {
+    let ppm = sample().to_ppm();
+    let header = b"P6\n2 2\n255\n";
+    assert_eq!(&ppm[..header.len()], header);
+    assert_eq!(
+        &ppm[header.len()..],
+        &[255, 0, 0, 127, 127, 255, 255, 255, 255, 255, 255, 255]
+    );
+}
+
+#[test]
+fn png_round_trips_with_alpha() {
+    let png = sample().to_png().unwrap();
+    let decoder = png::Decoder::new(png.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height), (2, 2));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(
+        &data[..info.buffer_size()],
+        &[255, 0, 0, 255, 0, 0, 255, 128, 0, 0, 0, 0, 255, 255, 255, 255]
+    );
+}
+
+#[test]
+fn save_picks_the_format_from_the_extension() {
+    let canvas = sample();
+    let dir = std::env::temp_dir();
+    let ppm = dir.join("rust-helloworld-test-canvas.ppm");
+    let png = dir.join("rust-helloworld-test-canvas.png");
+    canvas.save(&ppm).unwrap();
+    canvas.save(&png).unwrap();
+    assert_eq!(fs::read(&ppm).unwrap(), canvas.to_ppm());
+    assert_eq!(fs::read(&png).unwrap(), canvas.to_png().unwrap());
+    fs::remove_file(&ppm).unwrap();
+    fs::remove_file(&png).unwrap();
+
+    let bmp = dir.join("rust-helloworld-test-canvas.bmp");
+    let err = canvas.save(&bmp).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!bmp.exists());
+}
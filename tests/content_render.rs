@@ -0,0 +1,87 @@
+use rust_helloworld::content::{Html, Markdown, PlainText, Post, Render};
+use rust_helloworld::i18n::{set_locale, Locale};
+
+fn post() -> Post {
+    let mut post = Post::new(7, "Rust & <Web>", "Sunface", "第一段\n第二行\n\n第二段");
+    post.tag("Rust");
+    post.tag("web");
+    post
+}
+
+#[test]
+fn display_is_plain_text() {
+    set_locale(Locale::ZhCn);
+    let post = post();
+    assert_eq!(format!("{}", post), PlainText.render(&post));
+    assert_eq!(
+        post.to_string(),
+        "Rust & <Web>\n作者: Sunface\n标签: rust, web\n\n第一段\n第二行\n\n第二段"
+    );
+}
+
+#[test]
+fn plain_text_without_tags() {
+    set_locale(Locale::ZhCn);
+    let post = Post::new(1, "标题", "作者", "正文");
+    assert_eq!(PlainText.render(&post), "标题\n作者: 作者\n\n正文");
+}
+
+#[test]
+fn markdown_escapes_metadata_but_not_content() {
+    set_locale(Locale::ZhCn);
+    let mut post = Post::new(1, "[link](x) _a_", "a*b", "**粗体**\n\n");
+    post.tag("rust");
+    assert_eq!(
+        Markdown.render(&post),
+        "# \\[link\\](x) \\_a\\_\n\n*作者: a\\*b* · `rust`\n\n**粗体**\n"
+    );
+}
+
+#[test]
+fn markdown_escapes_html_in_tag_code() {
+    set_locale(Locale::ZhCn);
+    let content = "用 `Vec<T>` 和 `&str`\n\n```rust\nlet x = a < b && c > d;\n```\n";
+    let mut post = Post::new(1, "代码", "Tom", content);
+    post.tag("rust");
+    post.tag("<script>");
+    post.tag("a&b");
+    post.tag("``");
+    assert_eq!(
+        Markdown.render(&post),
+        "# 代码\n\n*作者: Tom* · `rust` <code>&lt;script&gt;</code> <code>a&amp;b</code> \
+         <code>``</code>\n\n\
+         用 `Vec<T>` 和 `&str`\n\n```rust\nlet x = a < b && c > d;\n```\n"
+    );
+}
+
+#[test]
+fn html_escapes_everything() {
+    let html = Html.render(&post());
+    assert_eq!(
+        html,
+        "<article>\n\
+         \x20 <h1>Rust &amp; &lt;Web&gt;</h1>\n\
+         \x20 <p class=\"author\">Sunface</p>\n\
+         \x20 <ul class=\"tags\"><li>rust</li><li>web</li></ul>\n\
+         \x20 <p>第一段<br>第二行</p>\n\
+         \x20 <p>第二段</p>\n\
+         </article>"
+    );
+
+    let post = Post::new(1, "t", "O'Neil", "<img src=x onerror=\"alert(1)\">");
+    let html = Html.render(&post);
+    assert!(!html.contains("<img"));
+    assert!(html.contains("O&#39;Neil"));
+    assert!(html.contains("&lt;img src=x onerror=&quot;alert(1)&quot;&gt;"));
+}
+
+#[test]
+fn renderers_are_selectable_at_runtime() {
+    let post = post();
+    let formats: Vec<Box<dyn Render>> =
+        vec![Box::new(PlainText), Box::new(Markdown), Box::new(Html)];
+    let outputs: Vec<String> = formats.iter().map(|f| post.render(f.as_ref())).collect();
+    assert!(outputs[0].starts_with("Rust & <Web>\n"));
+    assert!(outputs[1].starts_with("# Rust & \\<Web\\>\n"));
+    assert!(outputs[2].starts_with("<article>\n"));
+}
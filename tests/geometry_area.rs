@@ -0,0 +1,94 @@
+use proptest::prelude::*;
+
+use rust_helloworld::geometry::{Point, Rectangle};
+
+// 边界值：0、1、以及最大值附近
+fn boundary_u32() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        Just(0u32),
+        Just(1),
+        Just(u16::MAX as u32),
+        Just(u16::MAX as u32 + 1),
+        Just(u32::MAX - 1),
+        Just(u32::MAX),
+        any::<u32>(),
+    ]
+}
+
+fn boundary_i32() -> impl Strategy<Value = i32> {
+    prop_oneof![
+        Just(0i32),
+        Just(1),
+        Just(-1),
+        Just(i32::MIN),
+        Just(i32::MAX),
+        any::<i32>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn wide_area_is_exact(w in boundary_u32(), h in boundary_u32()) {
+        let rect = Rectangle::new(w, h);
+        prop_assert_eq!(rect.wide_area() as u128, w as u128 * h as u128);
+    }
+
+    #[test]
+    fn checked_area_matches_wide_area(w in boundary_u32(), h in boundary_u32()) {
+        let rect = Rectangle::new(w, h);
+        let wide = rect.wide_area();
+        match rect.checked_area() {
+            Some(area) => prop_assert_eq!(area as u64, wide),
+            None => prop_assert!(wide > u32::MAX as u64),
+        }
+    }
+
+    #[test]
+    fn saturating_area_clamps_to_max(w in boundary_u32(), h in boundary_u32()) {
+        let rect = Rectangle::new(w, h);
+        let expected = rect.wide_area().min(u32::MAX as u64) as u32;
+        prop_assert_eq!(rect.saturating_area(), expected);
+    }
+
+    #[test]
+    fn signed_areas(w in boundary_i32(), h in boundary_i32()) {
+        let rect = Rectangle::new(w, h);
+        let wide = w as i64 * h as i64;
+        prop_assert_eq!(rect.wide_area(), wide);
+        prop_assert_eq!(rect.checked_area().map(|a| a as i64), i32::try_from(wide).ok().map(|a| a as i64));
+        prop_assert_eq!(rect.saturating_area() as i64, wide.clamp(i32::MIN as i64, i32::MAX as i64));
+    }
+
+    #[test]
+    fn far_edges_saturate(x in boundary_u32(), y in boundary_u32(), w in boundary_u32(), h in boundary_u32()) {
+        let rect = Rectangle::at(Point::new(x, y), w, h);
+        prop_assert_eq!(rect.max_x() as u64, (x as u64 + w as u64).min(u32::MAX as u64));
+        prop_assert_eq!(rect.max_y() as u64, (y as u64 + h as u64).min(u32::MAX as u64));
+        prop_assert!(rect.contains(&Point::new(x, y)));
+    }
+
+    #[test]
+    fn signed_far_edges_saturate(x in boundary_i32(), w in boundary_i32()) {
+        let rect = Rectangle::at(Point::new(x, 0), w, 0);
+        let wide = (x as i64 + w as i64).clamp(i32::MIN as i64, i32::MAX as i64);
+        prop_assert_eq!(rect.max_x() as i64, wide);
+    }
+
+    #[test]
+    fn float_areas_never_overflow_silently(w in any::<f32>(), h in any::<f32>()) {
+        let rect = Rectangle::new(w, h);
+        if let Some(area) = rect.checked_area() {
+            prop_assert!(area.is_finite());
+        }
+        let saturated = rect.saturating_area();
+        prop_assert!(saturated.is_nan() || saturated.is_finite());
+    }
+}
+
+#[test]
+fn u64_area_widens_to_u128() {
+    let rect = Rectangle::new(u64::MAX, u64::MAX);
+    assert_eq!(rect.checked_area(), None);
+    assert_eq!(rect.saturating_area(), u64::MAX);
+    assert_eq!(rect.wide_area(), u64::MAX as u128 * u64::MAX as u128);
+}
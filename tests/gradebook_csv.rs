@@ -0,0 +1,79 @@
+use rust_helloworld::classroom::{Gradebook, GradebookError, CSV_HEADER};
+use rust_helloworld::i18n::{set_locale, Locale};
+
+fn book() -> Gradebook {
+    let mut book = Gradebook::new();
+    for student in [1, 2, 3] {
+        book.add_student(student);
+    }
+    book.add_category("homework", 30.0).unwrap();
+    book.add_category("exam", 70.0).unwrap();
+    book.add_category("project", 10.0).unwrap();
+    let hw = book
+        .add_assignment("hw \"one\",\nlong", "homework", 10.0)
+        .unwrap();
+    let exam = book.add_assignment("midterm", "exam", 50.0).unwrap();
+    book.add_assignment("final", "exam", 100.0).unwrap();
+    book.record(hw, 1, 7.5).unwrap();
+    book.record(exam, 1, 41.0).unwrap();
+    book.record(exam, 2, 0.0).unwrap();
+    book
+}
+
+#[test]
+fn round_trip_keeps_everything() {
+    let book = book();
+    let copy = Gradebook::from_csv(&book.to_csv()).unwrap();
+    assert_eq!(copy.categories(), book.categories());
+    assert_eq!(
+        copy.assignments().collect::<Vec<_>>(),
+        book.assignments().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        copy.students().collect::<Vec<_>>(),
+        book.students().collect::<Vec<_>>()
+    );
+    for student in [1, 2, 3] {
+        assert_eq!(copy.percent(student), book.percent(student));
+    }
+    assert_eq!(copy.to_csv(), book.to_csv());
+}
+
+#[test]
+fn accepts_crlf_and_blank_lines() {
+    let csv = format!(
+        "{}\r\nquiz,1,q1,4,5,3\r\n\r\nquiz,1,q1,4,6,4\r\n",
+        CSV_HEADER
+    );
+    let book = Gradebook::from_csv(&csv).unwrap();
+    assert_eq!(book.percent(5), Some(75.0));
+    assert_eq!(book.percent(6), Some(100.0));
+}
+
+#[test]
+fn reports_line_numbers() {
+    set_locale(Locale::EnUs);
+    let csv = format!("{}\nquiz,1,q1,4,5,3\nquiz,2,q2,4,5,3\n", CSV_HEADER);
+    let err = Gradebook::from_csv(&csv).unwrap_err();
+    assert_eq!(err.to_string(), "line 3: invalid weight: 2");
+
+    // 引号中的换行不会打乱后面的行号
+    let csv = format!("{}\nquiz,1,\"a\nb\",4,5,3\nquiz,1,q2,x,5,3\n", CSV_HEADER);
+    let err = Gradebook::from_csv(&csv).unwrap_err();
+    assert_eq!(err.to_string(), "line 4: invalid max_points: x");
+
+    let csv = format!("{}\nquiz,1,q1\n", CSV_HEADER);
+    assert!(matches!(
+        Gradebook::from_csv(&csv),
+        Err(GradebookError::Csv { line: 2, .. })
+    ));
+
+    assert!(matches!(
+        Gradebook::from_csv("student,points\n1,2\n"),
+        Err(GradebookError::Csv { line: 1, .. })
+    ));
+    assert!(matches!(
+        Gradebook::from_csv(&format!("{}\nquiz,1,\"q1,4,5,3\n", CSV_HEADER)),
+        Err(GradebookError::Csv { line: 2, .. })
+    ));
+}
@@ -0,0 +1,144 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use rust_helloworld::front_of_house::hosting::{add_to_waitlist, reserve, seat_at_table};
+use rust_helloworld::front_of_house::serving::{take_order, take_payment, OrderItem, OrderStatus};
+use rust_helloworld::storage::{JsonLinesStorage, Storage, StorageError};
+
+// 每个测试用自己的文件，并行运行时互不干扰
+fn data_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rust-helloworld-test-{}.jsonl", name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn append_raw(path: &PathBuf, bytes: &[u8]) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(bytes).unwrap();
+}
+
+#[test]
+fn round_trip_through_reopen() {
+    let path = data_file("round-trip");
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    let first = add_to_waitlist(&mut store, "张三", 2).unwrap();
+    add_to_waitlist(&mut store, "李四", 4).unwrap();
+    seat_at_table(&mut store, first.id, 5).unwrap();
+    reserve(&mut store, "王五", 6, 1_700_000_000).unwrap();
+    let items = vec![OrderItem::new("饺子", "主食", 1200, 2)];
+    let order = take_order(&mut store, "waiter", 5, 2, items).unwrap();
+    let payment = take_payment(&mut store, "cashier", order.id, 300)
+        .unwrap()
+        .unwrap();
+    drop(store);
+
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    let waitlist = store.waitlist().unwrap();
+    assert_eq!(waitlist.len(), 1);
+    assert_eq!(waitlist[0].name, "李四");
+    assert_eq!(store.reservations().unwrap()[0].name, "王五");
+    assert_eq!(
+        store.order(order.id).unwrap().unwrap().status,
+        OrderStatus::Paid
+    );
+    assert_eq!(store.payments().unwrap(), vec![payment.clone()]);
+    assert_eq!(store.events().unwrap().len(), 2);
+
+    // 重新打开之后分配的编号不会与已有记录重复
+    let next = store.next_id().unwrap();
+    assert!(next > payment.id && next > order.id);
+    drop(store);
+
+    let loaded = JsonLinesStorage::load(&path).unwrap();
+    assert_eq!(loaded.payments().unwrap(), vec![payment]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn torn_final_line_is_truncated() {
+    let path = data_file("torn-tail");
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    add_to_waitlist(&mut store, "张三", 2).unwrap();
+    drop(store);
+    let intact = fs::read(&path).unwrap();
+    // 写到一半的多字节字符也算是半行
+    let torn = "{\"op\":\"party_added\",\"id\":9,\"name\":\"李";
+    append_raw(&path, &torn.as_bytes()[..torn.len() - 1]);
+
+    // 只读打开时忽略半行，文件保持原样
+    assert_eq!(
+        JsonLinesStorage::load(&path)
+            .unwrap()
+            .waitlist()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(fs::read(&path).unwrap().len() > intact.len());
+
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), intact);
+    add_to_waitlist(&mut store, "李四", 4).unwrap();
+    drop(store);
+
+    let store = JsonLinesStorage::open(&path).unwrap();
+    let names: Vec<String> = store
+        .waitlist()
+        .unwrap()
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(names, vec!["张三", "李四"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn complete_final_line_without_newline_is_kept() {
+    let path = data_file("no-newline");
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    add_to_waitlist(&mut store, "张三", 2).unwrap();
+    drop(store);
+    let mut bytes = fs::read(&path).unwrap();
+    bytes.pop();
+    fs::write(&path, &bytes).unwrap();
+
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    add_to_waitlist(&mut store, "李四", 4).unwrap();
+    drop(store);
+
+    let store = JsonLinesStorage::open(&path).unwrap();
+    assert_eq!(store.waitlist().unwrap().len(), 2);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn bad_line_in_the_middle_is_corrupt() {
+    let path = data_file("corrupt");
+    let mut store = JsonLinesStorage::open(&path).unwrap();
+    add_to_waitlist(&mut store, "张三", 2).unwrap();
+    drop(store);
+    let intact = fs::read(&path).unwrap();
+    append_raw(&path, b"not json\n");
+    append_raw(&path, &intact);
+
+    for result in [
+        JsonLinesStorage::load(&path).map(|_| ()),
+        JsonLinesStorage::open(&path).map(|_| ()),
+    ] {
+        assert!(matches!(result, Err(StorageError::Corrupt { line: 2, .. })));
+    }
+    // 打开失败时不截断文件
+    assert_eq!(fs::read(&path).unwrap().len(), intact.len() * 2 + 9);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn load_fails_on_missing_file() {
+    let path = data_file("missing");
+    assert!(matches!(
+        JsonLinesStorage::load(&path),
+        Err(StorageError::Io(_))
+    ));
+    assert!(!path.exists());
+}
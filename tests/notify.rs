@@ -0,0 +1,196 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use rust_helloworld::notify::{
+    Backoff, Delivery, Dispatcher, FileNotifier, Notification, Notifier, NotifyError, Subscriber,
+    WebhookNotifier,
+};
+
+/// 本地的 webhook 桩，按顺序用 `statuses` 中的状态码响应，把收到的请求体发回测试
+fn stub(statuses: Vec<u16>) -> (SocketAddr, Receiver<(String, String)>) {
+    stub_on(TcpListener::bind("127.0.0.1:0").unwrap(), statuses)
+}
+
+fn stub_on(listener: TcpListener, statuses: Vec<u16>) -> (SocketAddr, Receiver<(String, String)>) {
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            tx.send((
+                request_line.trim().to_string(),
+                String::from_utf8(body).unwrap(),
+            ))
+            .unwrap();
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+        }
+    });
+    (addr, rx)
+}
+
+fn fast_retry(retries: u32) -> Backoff {
+    Backoff::new(retries, Duration::from_millis(1))
+}
+
+#[test]
+fn webhook_posts_json() {
+    let (addr, rx) = stub(vec![200]);
+    let mut webhook = WebhookNotifier::new(&format!("http://{}/hooks/news", addr)).unwrap();
+    let notification = Notification::new("news", "Rust 1.0 发布了");
+    webhook.notify(&notification).unwrap();
+
+    let (request_line, body) = rx.recv().unwrap();
+    assert_eq!(request_line, "POST /hooks/news HTTP/1.1");
+    let received: Notification = serde_json::from_str(&body).unwrap();
+    assert_eq!(received, notification);
+}
+
+#[test]
+fn webhook_accepts_bracketed_ipv6() {
+    assert!(WebhookNotifier::new("http://[::1]/hook").is_ok());
+    // 没有 IPv6 的环境下只检查解析
+    let listener = match TcpListener::bind("[::1]:0") {
+        Ok(listener) => listener,
+        Err(_) => return,
+    };
+    let (addr, rx) = stub_on(listener, vec![204]);
+    let url = format!("http://[::1]:{}/hooks/v6", addr.port());
+    let mut webhook = WebhookNotifier::new(&url).unwrap();
+    webhook.notify(&Notification::new("news", "v6")).unwrap();
+    let (request_line, _) = rx.recv().unwrap();
+    assert_eq!(request_line, "POST /hooks/v6 HTTP/1.1");
+}
+
+#[test]
+fn webhook_rejects_unsupported_urls() {
+    for url in [
+        "https://example.com/hook",
+        "example.com",
+        "http://:80/",
+        "http://host:x/",
+        "http://::1/",
+        "http://[::1/",
+        "http://[::1]x/",
+        "http://[::1]:x/",
+        "http://[]:80/",
+    ] {
+        assert!(matches!(
+            WebhookNotifier::new(url),
+            Err(NotifyError::InvalidUrl(_))
+        ));
+    }
+}
+
+#[test]
+fn retries_server_errors_with_backoff() {
+    let (addr, rx) = stub(vec![500, 503, 204]);
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(
+        Subscriber::new(WebhookNotifier::new(&format!("http://{}/", addr)).unwrap())
+            .backoff(fast_retry(3)),
+    );
+
+    let results = dispatcher.publish(&Notification::new("news", "hi"));
+    assert!(matches!(results[0].1, Delivery::Delivered { attempts: 3 }));
+    assert_eq!(rx.try_iter().count(), 3);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let (addr, _rx) = stub(vec![400]);
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(
+        Subscriber::new(WebhookNotifier::new(&format!("http://{}/", addr)).unwrap())
+            .backoff(fast_retry(3)),
+    );
+
+    let results = dispatcher.publish(&Notification::new("news", "hi"));
+    match &results[0].1 {
+        Delivery::Failed { attempts, error } => {
+            assert_eq!(*attempts, 1);
+            assert!(matches!(error, NotifyError::Status(400)));
+        }
+        other => panic!("unexpected delivery {:?}", other),
+    }
+}
+
+#[test]
+fn gives_up_after_retries() {
+    let (addr, _rx) = stub(vec![502, 502]);
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(
+        Subscriber::new(
+            WebhookNotifier::new(&format!("http://{}/", addr))
+                .unwrap()
+                .timeout(Duration::from_millis(200)),
+        )
+        .backoff(fast_retry(1)),
+    );
+
+    let results = dispatcher.publish(&Notification::new("news", "hi"));
+    assert!(matches!(
+        results[0].1,
+        Delivery::Failed {
+            attempts: 2,
+            error: NotifyError::Status(502)
+        }
+    ));
+}
+
+#[test]
+fn topics_filter_and_rate_limits_per_subscriber() {
+    let path = std::env::temp_dir().join(format!("notify-test-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut dispatcher = Dispatcher::new();
+    let file = dispatcher.subscribe(
+        Subscriber::new(FileNotifier::new(&path))
+            .topic("news.*")
+            .rate_limit(2, Duration::from_secs(60)),
+    );
+
+    for topic in ["news.rust", "news", "sports", "news.go", "news.rust.1"] {
+        dispatcher.publish(&Notification::new(topic, topic));
+    }
+
+    let lines: Vec<Notification> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let topics: Vec<&str> = lines.iter().map(|n| n.topic.as_str()).collect();
+    // `news` 和 `sports` 不匹配，第三条 news.* 超出频率
+    assert_eq!(topics, vec!["news.rust", "news.go"]);
+
+    assert!(dispatcher.unsubscribe(file));
+    assert!(!dispatcher.unsubscribe(file));
+    assert!(dispatcher
+        .publish(&Notification::new("news.rust", "x"))
+        .is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
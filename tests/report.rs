@@ -0,0 +1,143 @@
+use serde_json::json;
+
+use rust_helloworld::front_of_house::serving::{Order, OrderItem, OrderStatus, Payment};
+use rust_helloworld::report::ZReport;
+
+const DAY: u64 = 24 * 3600;
+// 2024-01-01 00:00 UTC
+const MONDAY: u64 = 19723 * DAY;
+
+fn order(
+    id: u64,
+    covers: u32,
+    items: Vec<OrderItem>,
+    status: OrderStatus,
+    opened_at: u64,
+) -> Order {
+    Order {
+        id,
+        table: 1,
+        covers,
+        items,
+        status,
+        opened_at,
+    }
+}
+
+fn payment(id: u64, order_id: u64, amount: u64, tip: u64, paid_at: u64) -> Payment {
+    Payment {
+        id,
+        order_id,
+        amount,
+        tip,
+        paid_at,
+    }
+}
+
+// 23:30 下单、第二天 0:30 付款的订单，以及第二天 0:10 下单的订单
+fn sample() -> (Vec<Order>, Vec<Payment>) {
+    let orders = vec![
+        order(
+            1,
+            3,
+            vec![
+                OrderItem::new("饺子", "主食", 1200, 2),
+                OrderItem::new("茶, 热", "饮品", 300, 1).comp(),
+            ],
+            OrderStatus::Paid,
+            MONDAY + DAY - 1800,
+        ),
+        order(
+            2,
+            2,
+            vec![OrderItem::new("汤", "热菜", 900, 1)],
+            OrderStatus::Voided,
+            MONDAY + 3600 * 12,
+        ),
+        order(
+            3,
+            1,
+            vec![OrderItem::new("面", "主食", 1500, 1)],
+            OrderStatus::Paid,
+            MONDAY + DAY + 600,
+        ),
+    ];
+    let payments = vec![
+        payment(10, 1, 2400, 300, MONDAY + DAY + 1800),
+        payment(11, 3, 1500, 100, MONDAY + DAY + 900),
+    ];
+    (orders, payments)
+}
+
+#[test]
+fn tips_follow_the_day_of_their_order() {
+    let (orders, payments) = sample();
+
+    let monday = ZReport::build(&orders, &payments, MONDAY + 3600);
+    assert_eq!(monday.checks, 1);
+    assert_eq!(monday.gross_sales, 2400);
+    assert_eq!(monday.tips, 300);
+
+    let tuesday = ZReport::build(&orders, &payments, MONDAY + DAY + 3600);
+    assert_eq!(tuesday.checks, 1);
+    assert_eq!(tuesday.gross_sales, 1500);
+    assert_eq!(tuesday.tips, 100);
+}
+
+#[test]
+fn csv_output() {
+    let (orders, payments) = sample();
+    let report = ZReport::build(&orders, &payments, MONDAY);
+    assert_eq!(
+        report.to_csv(),
+        "section,key,quantity,amount\n\
+         summary,gross_sales,1,2400\n\
+         summary,covers,3,\n\
+         summary,average_check,,2400\n\
+         summary,average_per_cover,,800\n\
+         summary,tips,1,300\n\
+         summary,voids,1,900\n\
+         summary,comps,1,300\n\
+         category,主食,2,2400\n\
+         item,饺子,2,2400\n\
+         hour,23,1,2400\n"
+    );
+}
+
+#[test]
+fn json_output() {
+    let (orders, payments) = sample();
+    let report = ZReport::build(&orders, &payments, MONDAY);
+    let value: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "day": MONDAY,
+            "checks": 1,
+            "covers": 3,
+            "gross_sales": 2400,
+            "tips": 300,
+            "average_check": 2400,
+            "average_per_cover": 800,
+            "voids": { "count": 1, "amount": 900 },
+            "comps": { "count": 1, "amount": 300 },
+            "by_category": [{ "key": "主食", "quantity": 2, "amount": 2400 }],
+            "by_item": [{ "key": "饺子", "quantity": 2, "amount": 2400 }],
+            "by_hour": [{ "key": "23", "quantity": 1, "amount": 2400 }],
+        })
+    );
+}
+
+#[test]
+fn csv_quotes_keys_with_commas() {
+    let orders = vec![order(
+        1,
+        1,
+        vec![OrderItem::new("茶, 热", "饮品", 300, 1)],
+        OrderStatus::Paid,
+        MONDAY,
+    )];
+    let report = ZReport::build(&orders, &[], MONDAY);
+    assert!(report.to_csv().contains("item,\"茶, 热\",1,300\n"));
+    assert_eq!(report.tips, 0);
+}
@@ -0,0 +1,54 @@
+use rust_helloworld::action::Action;
+use rust_helloworld::i18n::{set_locale, Locale};
+use rust_helloworld::script::compile;
+
+#[test]
+fn deeply_nested_parens_are_rejected() {
+    set_locale(Locale::EnUs);
+    let source = format!("say {}1{}", "(".repeat(100_000), ")".repeat(100_000));
+    let err = compile(&source).unwrap_err();
+    assert_eq!(err.to_string(), "1:133: nested deeper than 128 levels");
+}
+
+#[test]
+fn deeply_nested_negation_and_blocks_are_rejected() {
+    set_locale(Locale::EnUs);
+    let source = format!("say {}1", "-".repeat(100_000));
+    assert!(compile(&source).unwrap_err().message.contains("128"));
+
+    let source = format!("{}say 1{}", "repeat 1 { ".repeat(200), " }".repeat(200));
+    assert!(compile(&source).unwrap_err().message.contains("128"));
+}
+
+#[test]
+fn long_operator_chains_are_rejected() {
+    set_locale(Locale::EnUs);
+    let source = format!("say 1{}", " + 1".repeat(100_000));
+    assert!(compile(&source).unwrap_err().message.contains("128"));
+}
+
+#[test]
+fn nesting_within_the_limit_compiles() {
+    let source = format!("say {}1{}", "(".repeat(100), ")".repeat(100));
+    assert_eq!(
+        compile(&source).unwrap(),
+        vec![Action::Say("1".to_string())]
+    );
+
+    let source = format!("say 1{}", " + 1".repeat(100));
+    assert_eq!(
+        compile(&source).unwrap(),
+        vec![Action::Say("101".to_string())]
+    );
+}
+
+#[test]
+fn total_output_is_capped() {
+    set_locale(Locale::EnUs);
+    let source = "let s = \"ab\"\nrepeat 15 { s = s + s }\nrepeat 100 { say s }";
+    let err = compile(source).unwrap_err();
+    assert_eq!(err.to_string(), "3:14: script says more than 1048576 bytes");
+
+    let source = "let s = \"ab\"\nrepeat 15 { s = s + s }\nrepeat 15 { say s }";
+    assert_eq!(compile(source).unwrap().len(), 15);
+}
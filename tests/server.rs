@@ -0,0 +1,209 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use rust_helloworld::server::Server;
+use rust_helloworld::storage::MemoryStorage;
+
+fn start() -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", Box::new(MemoryStorage::new())).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    addr
+}
+
+/// 返回完整的响应，包括状态行和响应头
+fn send(addr: SocketAddr, method: &str, path: &str, body: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nX-Actor: tablet-1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+fn request(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let response = send(addr, method, path, &body);
+    let status = response[9..12].parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn waitlist_and_seating() {
+    let addr = start();
+
+    let (status, party) = request(
+        addr,
+        "POST",
+        "/waitlist",
+        Some(json!({"name": "Ann", "size": 2})),
+    );
+    assert_eq!(status, 201);
+    assert_eq!(party["size"], 2);
+
+    let (_, waitlist) = request(addr, "GET", "/waitlist", None);
+    assert_eq!(waitlist.as_array().unwrap().len(), 1);
+
+    let path = format!("/waitlist/{}/seat", party["id"]);
+    let (status, seating) = request(addr, "POST", &path, Some(json!({"table": 5})));
+    assert_eq!(status, 200);
+    assert_eq!(seating["table"], 5);
+
+    let (_, waitlist) = request(addr, "GET", "/waitlist", None);
+    assert!(waitlist.as_array().unwrap().is_empty());
+
+    let (status, _) = request(addr, "POST", &path, Some(json!({"table": 5})));
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn reservations() {
+    let addr = start();
+
+    let body = json!({"name": "Bo", "size": 4, "time": 1700000000});
+    let (status, reservation) = request(addr, "POST", "/reservations", Some(body));
+    assert_eq!(status, 201);
+
+    let path = format!("/reservations/{}", reservation["id"]);
+    let (status, _) = request(addr, "DELETE", &path, None);
+    assert_eq!(status, 200);
+    let (status, _) = request(addr, "DELETE", &path, None);
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn order_lifecycle() {
+    let addr = start();
+
+    let items = json!([{"name": "dumplings", "category": "main", "price": 1200, "quantity": 2}]);
+    let (status, order) = request(
+        addr,
+        "POST",
+        "/orders",
+        Some(json!({"table": 3, "covers": 2, "items": items})),
+    );
+    assert_eq!(status, 201);
+    assert_eq!(order["status"], "open");
+    let path = format!("/orders/{}", order["id"]);
+
+    let items = json!([{"name": "dumplings", "category": "main", "price": 1200, "quantity": 3}]);
+    let (_, order) = request(addr, "PUT", &path, Some(json!({ "items": items })));
+    assert_eq!(order["items"][0]["quantity"], 3);
+
+    let (_, order) = request(addr, "POST", &format!("{}/serve", path), None);
+    assert_eq!(order["status"], "served");
+
+    let (status, payment) = request(
+        addr,
+        "POST",
+        &format!("{}/payments", path),
+        Some(json!({"tip": 500})),
+    );
+    assert_eq!(status, 201);
+    assert_eq!(payment["amount"], 3600);
+    assert_eq!(payment["tip"], 500);
+
+    let (_, order) = request(addr, "GET", &path, None);
+    assert_eq!(order["status"], "paid");
+    let (_, payments) = request(addr, "GET", "/payments", None);
+    assert_eq!(payments.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn void_order() {
+    let addr = start();
+
+    let (_, order) = request(
+        addr,
+        "POST",
+        "/orders",
+        Some(json!({"table": 1, "covers": 1, "items": []})),
+    );
+    let path = format!("/orders/{}/void", order["id"]);
+    let (_, order) = request(addr, "POST", &path, Some(json!({"reason": "walked out"})));
+    assert_eq!(order["status"], "voided");
+
+    let (status, body) = request(addr, "POST", &path, Some(json!({"reason": "again"})));
+    assert_eq!(status, 409);
+    assert!(body["error"].is_string());
+}
+
+#[test]
+fn closed_orders_reject_changes() {
+    let addr = start();
+
+    let (_, order) = request(
+        addr,
+        "POST",
+        "/orders",
+        Some(json!({"table": 2, "covers": 2, "items": []})),
+    );
+    let path = format!("/orders/{}", order["id"]);
+    let (status, _) = request(addr, "POST", &format!("{}/payments", path), Some(json!({})));
+    assert_eq!(status, 201);
+
+    let (status, _) = request(addr, "POST", &format!("{}/void", path), Some(json!({})));
+    assert_eq!(status, 409);
+    let (status, _) = request(addr, "POST", &format!("{}/serve", path), Some(json!({})));
+    assert_eq!(status, 409);
+    let (_, order) = request(addr, "GET", &path, None);
+    assert_eq!(order["status"], "paid");
+}
+
+#[test]
+fn empty_body_uses_defaults() {
+    let addr = start();
+
+    let new_order = || Some(json!({"table": 4, "covers": 1, "items": []}));
+    let (_, order) = request(addr, "POST", "/orders", new_order());
+    let path = format!("/orders/{}/payments", order["id"]);
+    let (status, payment) = request(addr, "POST", &path, None);
+    assert_eq!(status, 201);
+    assert_eq!(payment["tip"], 0);
+
+    let (_, order) = request(addr, "POST", "/orders", new_order());
+    let path = format!("/orders/{}/void", order["id"]);
+    let response = send(addr, "POST", &path, "  \n");
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains(r#""status":"voided""#));
+}
+
+#[test]
+fn wrong_method_on_known_path() {
+    let addr = start();
+
+    let response = send(addr, "DELETE", "/orders", "");
+    assert!(response.starts_with("HTTP/1.1 405"));
+    assert!(response.contains("Allow: GET, POST\r\n"));
+
+    let (status, _) = request(addr, "GET", "/orders/1/void", None);
+    assert_eq!(status, 405);
+    let (status, _) = request(addr, "DELETE", "/kitchen", None);
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn bad_requests() {
+    let addr = start();
+
+    let (status, body) = request(addr, "POST", "/waitlist", Some(json!({"name": "Cy"})));
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+
+    let (status, _) = request(addr, "GET", "/orders/abc", None);
+    assert_eq!(status, 404);
+    let (status, _) = request(addr, "GET", "/kitchen", None);
+    assert_eq!(status, 404);
+}